@@ -0,0 +1,233 @@
+use crate::{Hashable, PrimeField};
+use ring::digest::Context;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// The `BabyBear` struct is an element of the prime field of modulus `p = 15 * 2^27 + 1`, stored in a `u32`
+/// in Montgomery form, `a * R mod p` with `R = 2^32`. Products fit in a `u64` and reduce without a division,
+/// and the 2^27 two-adicity of `p - 1` supports domains of up to 2^27 points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct BabyBear {
+    value: u32, // `a * R mod p` for the element `a`.
+}
+
+impl BabyBear {
+    // The prime modulus `15 * 2^27 + 1`.
+    pub const MODULUS: u32 = 0x7800_0001;
+
+    // `R^2 mod p`, which maps an integer to Montgomery form in one reduction.
+    const R2: u32 = ((u64::MAX % Self::MODULUS as u64 + 1) % Self::MODULUS as u64) as u32;
+
+    // `p^-1 mod R`, by Newton's iteration: each step doubles the number of correct low bits,
+    // starting from `p`, which is its own inverse modulo 8.
+    const INV: u32 = {
+        let mut inv = Self::MODULUS;
+        let mut i = 0;
+        while i < 4 {
+            inv = inv.wrapping_mul(2u32.wrapping_sub(Self::MODULUS.wrapping_mul(inv)));
+            i += 1;
+        }
+        inv
+    };
+
+    // The smallest generator of the multiplicative group.
+    pub const GENERATOR: BabyBear = BabyBear::new(31);
+
+    // The largest `k` such that `2^k` divides `p - 1`.
+    pub const TWO_ADICITY: u32 = 27;
+
+    // Creates the element `n mod p`.
+    pub const fn new(n: u32) -> Self {
+        Self {
+            value: Self::reduce(n as u64 * Self::R2 as u64),
+        }
+    }
+
+    // Returns the canonical value of the element, in `[0, p)`.
+    pub fn value(&self) -> u32 {
+        Self::reduce(self.value as u64)
+    }
+
+    // Returns a primitive root of unity of order `2^log_size`, for `log_size` up to the two-adicity.
+    pub fn root_of_unity(log_size: u32) -> Option<Self> {
+        (log_size <= Self::TWO_ADICITY)
+            .then(|| Self::GENERATOR.pow(((Self::MODULUS - 1) >> log_size) as u128))
+    }
+
+    // Montgomery reduction: returns `t / R mod p` for `t < p * R`.
+    // With `m = t * p^-1 mod R`, `t - m * p` is a multiple of `R` whose quotient lies in `(-p, p)`.
+    const fn reduce(t: u64) -> u32 {
+        let m = (t as u32).wrapping_mul(Self::INV);
+        let mp_high = ((m as u64 * Self::MODULUS as u64) >> 32) as u32;
+        let (result, borrow) = ((t >> 32) as u32).overflowing_sub(mp_high);
+        if borrow {
+            result.wrapping_add(Self::MODULUS)
+        } else {
+            result
+        }
+    }
+}
+
+// Implements the `PrimeField` trait so FRI can run over `BabyBear`; the modulus is fixed, so `self` is unused.
+impl PrimeField for BabyBear {
+    fn zero(&self) -> Self {
+        BabyBear { value: 0 }
+    }
+
+    fn one(&self) -> Self {
+        BabyBear::new(1)
+    }
+
+    fn integer(&self, n: u64) -> Self {
+        BabyBear::new((n % Self::MODULUS as u64) as u32)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
+    fn is_reduced(&self) -> bool {
+        self.value < Self::MODULUS
+    }
+
+    fn group_order(&self) -> u128 {
+        Self::MODULUS as u128 - 1
+    }
+
+    fn generator(&self) -> Self {
+        Self::GENERATOR
+    }
+
+    // The canonical value as 4 big-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.value().to_be_bytes().to_vec()
+    }
+
+    // 128 bits of the input are reduced modulo the prime, which keeps the bias negligible.
+    fn sample(&self, bytes: &[u8]) -> Self {
+        let mut array = [0u8; 16];
+        array.copy_from_slice(&bytes[0..16]);
+
+        BabyBear::new((u128::from_be_bytes(array) % Self::MODULUS as u128) as u32)
+    }
+}
+
+// Implements the `Hashable` trait to make `BabyBear` usable in a Merkle tree.
+// The canonical value is hashed, so the commitment does not depend on the representation.
+impl Hashable for BabyBear {
+    fn update_context(&self, context: &mut Context) {
+        context.update(&self.value().to_le_bytes());
+    }
+}
+
+// Implements the `Add` trait; the sum of two reduced values fits in a `u32` as `p < 2^31`.
+impl Add for BabyBear {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let sum = self.value + rhs.value;
+        let value = if sum >= Self::MODULUS {
+            sum - Self::MODULUS
+        } else {
+            sum
+        };
+
+        BabyBear { value }
+    }
+}
+
+// Implements the `Sub` trait for subtraction of `BabyBear` elements.
+impl Sub for BabyBear {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let (difference, borrow) = self.value.overflowing_sub(rhs.value);
+        let value = if borrow {
+            difference.wrapping_add(Self::MODULUS)
+        } else {
+            difference
+        };
+
+        BabyBear { value }
+    }
+}
+
+// Implements the `Mul` trait: `(aR)(bR) / R = abR`, a single Montgomery reduction.
+impl Mul for BabyBear {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        BabyBear {
+            value: BabyBear::reduce(self.value as u64 * rhs.value as u64),
+        }
+    }
+}
+
+// Implements the `Div` trait for division of `BabyBear` elements.
+impl Div for BabyBear {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(rhs.value != 0, "divide by 0");
+
+        self * rhs.inverse()
+    }
+}
+
+// Implements the `Neg` trait for negation of `BabyBear` elements.
+impl Neg for BabyBear {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        BabyBear { value: 0 } - self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::montgomery::testing::{
+        assert_fri_round_trip, assert_matches_montgomery, sample_values,
+    };
+    use crate::MontgomeryField;
+
+    #[test]
+    fn arithmetic_matches_the_montgomery_field() {
+        let one = BabyBear::new(1);
+        assert_matches_montgomery(one, BabyBear::MODULUS as u64, |x| x.value() as u64);
+
+        // `new` reduces any `u32`, including the values above `p`
+        for a in sample_values(BabyBear::MODULUS as u64) {
+            let a = a as u32;
+            assert_eq!(BabyBear::new(a), one.integer(a as u64));
+        }
+    }
+
+    #[test]
+    fn generator_and_roots_of_unity() {
+        let one = BabyBear::new(1);
+        assert_eq!(std::mem::size_of::<BabyBear>(), 4);
+        assert_eq!(one.two_adicity(), BabyBear::TWO_ADICITY);
+        assert_eq!(
+            BabyBear::GENERATOR.value() as u64,
+            MontgomeryField::new(BabyBear::MODULUS as u64)
+                .generator()
+                .value()
+        );
+
+        for log_size in [0, 1, 8, 27] {
+            let root = BabyBear::root_of_unity(log_size).unwrap();
+            assert_eq!(root, one.primitive_nth_root(1 << log_size).unwrap());
+            assert_eq!(root.pow(1 << log_size), one);
+            if log_size > 0 {
+                assert_eq!(root.pow(1 << (log_size - 1)), -one);
+            }
+        }
+        assert_eq!(BabyBear::root_of_unity(28), None);
+    }
+
+    #[test]
+    fn fri_over_babybear() {
+        assert_fri_round_trip(BabyBear::new(1));
+    }
+}
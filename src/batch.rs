@@ -0,0 +1,402 @@
+use crate::{
+    check_reduced, prove_fri_with_transcript, verify_auth_path, verify_fri_with_transcript,
+};
+use crate::{proof_from_bytes, proof_to_bytes, MerkleTree, Proof, VerificationError};
+use crate::{EvaluationDomain, FriError, FriParams, FriProof, PrimeField, ProofStream};
+use serde::{Deserialize, Serialize};
+
+// Transcript labels of the batching step, absorbed before the FRI commit phase.
+const BATCH_ROOT_LABEL: &str = "fri_batch_root";
+const BATCH_GAMMA_LABEL: &str = "fri_batch_gamma";
+
+/// The `BatchOpening` struct holds, for one query, the coset of `folding_factor` evaluations
+/// of every original codeword and the authentication path of that leaf in the batch commitment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchOpening<F> {
+    pub auth_path: Option<Proof<Vec<Vec<F>>>>,
+    pub evaluations: Vec<Vec<F>>, // One coset per original codeword, in commit order.
+}
+
+/// The `BatchedFriProof` struct proves that several codewords over the same domain are each close
+/// to a polynomial of their own degree bound, with a single FRI instance on their random linear combination.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchedFriProof<F> {
+    pub batch_root: Vec<u8>, // Merkle root over the cosets of all the original codewords.
+    pub openings: Vec<BatchOpening<F>>, // One opening of the original codewords per query.
+    pub fri_proof: FriProof<F>, // Proof of proximity of the combined codeword.
+}
+
+impl<F: PrimeField> BatchedFriProof<F> {
+    // Serializes the batched proof with `proof_to_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        proof_to_bytes(self)
+    }
+
+    // Reconstructs the batched proof from the bytes produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        proof_from_bytes(bytes)
+    }
+}
+
+// Proves that every codeword `codewords[i]` over `domain` is close to a polynomial of degree below
+// `degree_bounds[i]`, each bound being at most `params.degree_bound()`.
+// All codewords are committed in one Merkle tree, then a challenge `gamma` is drawn from the transcript
+// and FRI runs on the combination
+//
+//     sum_i f_i(x) * (gamma^(2i) + gamma^(2i+1) * x^(D - d_i))
+//
+// where `D = params.degree_bound()`. The second term lifts each codeword to the full degree bound, so a
+// codeword exceeding its own bound also makes the combination exceed `D`.
+pub fn batch_prove<F: PrimeField>(
+    codewords: Vec<Vec<F>>, // Committed values of each polynomial, one per domain element.
+    degree_bounds: &[usize], // Claimed strict upper bound on the degree of each codeword.
+    domain: &EvaluationDomain<F>, // Domain shared by all the codewords.
+    params: &FriParams,     // Parameters of the FRI instance on the combination.
+) -> Result<BatchedFriProof<F>, FriError> {
+    check_batch(codewords.len(), degree_bounds, domain, params)?;
+    if let Some(codeword) = codewords.iter().find(|c| c.len() != domain.size) {
+        return Err(FriError::CodewordLengthMismatch {
+            expected: domain.size,
+            found: codeword.len(),
+        });
+    }
+
+    // commit to all the codewords at once, grouping the values that fold together like `FriLayer` does
+    let leaf_count = domain.size / params.folding_factor();
+    let leaves = (0..leaf_count)
+        .map(|j| leaf_values(&codewords, j, leaf_count, params.folding_factor()))
+        .collect();
+    let tree = MerkleTree::from_vec(params.hash().algorithm(), leaves);
+
+    let mut transcript = ProofStream::new();
+    transcript.absorb(BATCH_ROOT_LABEL, tree.root_hash());
+    let gamma = transcript.squeeze_field_element(BATCH_GAMMA_LABEL, &domain.generator);
+
+    // combine the codewords into a single one of degree below `params.degree_bound()`
+    let combined = domain
+        .iter()
+        .enumerate()
+        .map(|(position, x)| {
+            let values: Vec<F> = codewords.iter().map(|c| c[position]).collect();
+            degree_adjusted_combination(&values, x, &gamma, degree_bounds, params.degree_bound())
+        })
+        .collect();
+
+    let fri_proof = prove_fri_with_transcript(combined, domain, params, &mut transcript);
+
+    // open the original codewords at the leaves opened in the first FRI layer
+    let openings = fri_proof
+        .decommitments
+        .iter()
+        .map(|decommitment| {
+            let leaf_index = decommitment.layers_auth_paths[0]
+                .as_ref()
+                .map(|path| path.index)
+                .expect("the first layer is always opened");
+            BatchOpening {
+                auth_path: tree.gen_nth_proof(leaf_index),
+                evaluations: leaf_values(
+                    &codewords,
+                    leaf_index,
+                    leaf_count,
+                    params.folding_factor(),
+                ),
+            }
+        })
+        .collect();
+
+    Ok(BatchedFriProof {
+        batch_root: tree.root_hash().clone(),
+        openings,
+        fri_proof,
+    })
+}
+
+// Verifies a proof produced by `batch_prove` for the same degree bounds, domain and parameters.
+// Beyond the FRI checks on the combination, every query opens the original codewords in the batch
+// commitment and recombines them, which must give the values FRI opened in the combined codeword:
+// its first layer, or with DEEP sampling the codeword committed before taking the quotient.
+pub fn batch_verify<F: PrimeField>(
+    proof: &BatchedFriProof<F>,   // Proof produced by `batch_prove`.
+    degree_bounds: &[usize],      // Claimed strict upper bound on the degree of each codeword.
+    domain: &EvaluationDomain<F>, // Domain shared by all the codewords.
+    params: &FriParams,           // Parameters of the FRI instance on the combination.
+) -> Result<(), VerificationError> {
+    check_batch(degree_bounds.len(), degree_bounds, domain, params)?;
+
+    // replay the batching step, then the FRI instance on the same transcript
+    let mut transcript = ProofStream::new();
+    transcript.absorb(BATCH_ROOT_LABEL, &proof.batch_root);
+    let gamma = transcript.squeeze_field_element(BATCH_GAMMA_LABEL, &domain.generator);
+
+    let query_indices =
+        verify_fri_with_transcript(&proof.fri_proof, domain, params, &mut transcript)?;
+    if proof.openings.len() != query_indices.len() {
+        return Err(VerificationError::QueryCountMismatch {
+            expected: query_indices.len(),
+            found: proof.openings.len(),
+        });
+    }
+
+    let folding_factor = params.folding_factor();
+    let leaf_count = domain.size / folding_factor;
+    for (query_index, ((opening, coset), &index)) in proof
+        .openings
+        .iter()
+        .zip(proof.fri_proof.committed_cosets())
+        .zip(query_indices.iter())
+        .enumerate()
+    {
+        // the opening must hold one coset per codeword, authenticated at the leaf of the query
+        let leaf_index = index % leaf_count;
+        let authenticated = opening.evaluations.len() == degree_bounds.len()
+            && opening
+                .evaluations
+                .iter()
+                .all(|coset| coset.len() == folding_factor)
+            && verify_auth_path(
+                &opening.auth_path,
+                params.hash().algorithm(),
+                &proof.batch_root,
+                &opening.evaluations,
+                leaf_index,
+                leaf_count,
+            );
+        if !authenticated {
+            return Err(VerificationError::OpeningAuthentication { query_index });
+        }
+        check_reduced(opening.evaluations.iter().flatten(), &domain.generator)?;
+
+        // the combination of the opened values must be what FRI ran on
+        for t in 0..folding_factor {
+            let x = domain.element(leaf_index + t * leaf_count);
+            let values: Vec<F> = opening.evaluations.iter().map(|c| c[t]).collect();
+            let combined = degree_adjusted_combination(
+                &values,
+                x,
+                &gamma,
+                degree_bounds,
+                params.degree_bound(),
+            );
+            if coset[t] != combined {
+                return Err(VerificationError::FirstLayerMismatch { query_index });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Checks the shape of a batch: at least one codeword, one degree bound per codeword, each between 1
+// and `params.degree_bound()`, over a domain of the size the parameters require.
+fn check_batch<F: PrimeField>(
+    number_of_codewords: usize,
+    degree_bounds: &[usize],
+    domain: &EvaluationDomain<F>,
+    params: &FriParams,
+) -> Result<(), FriError> {
+    if number_of_codewords == 0 || number_of_codewords != degree_bounds.len() {
+        return Err(FriError::BatchSizeMismatch {
+            codewords: number_of_codewords,
+            degree_bounds: degree_bounds.len(),
+        });
+    }
+    if domain.size != params.domain_size() {
+        return Err(FriError::DomainSizeMismatch {
+            expected: params.domain_size(),
+            found: domain.size,
+        });
+    }
+    if let Some(&degree_bound) = degree_bounds
+        .iter()
+        .find(|&&bound| bound == 0 || bound > params.degree_bound())
+    {
+        return Err(FriError::InvalidDegreeBound {
+            degree_bound,
+            domain_size: domain.size,
+        });
+    }
+
+    Ok(())
+}
+
+// Returns the leaf `j` of the batch commitment: for each codeword, its values at the positions
+// `j + t * leaf_count` that fold together.
+fn leaf_values<F: PrimeField>(
+    codewords: &[Vec<F>],
+    j: usize,
+    leaf_count: usize,
+    folding_factor: usize,
+) -> Vec<Vec<F>> {
+    codewords
+        .iter()
+        .map(|codeword| {
+            (0..folding_factor)
+                .map(|t| codeword[j + t * leaf_count])
+                .collect()
+        })
+        .collect()
+}
+
+// Combines the values `f_i(x)` at the point `x` into `sum_i f_i(x) * (gamma^(2i) + gamma^(2i+1) * x^(D - d_i))`,
+// where `d_i` is the degree bound of `f_i` and `D` the common one, so the result has degree below `D`.
+pub fn degree_adjusted_combination<F: PrimeField>(
+    values: &[F],
+    x: F,
+    gamma: &F,
+    degree_bounds: &[usize],
+    max_degree_bound: usize,
+) -> F {
+    let mut coefficient = x.one();
+    let mut combined = x.zero();
+    for (value, &degree_bound) in values.iter().zip(degree_bounds.iter()) {
+        let shift = x.pow((max_degree_bound - degree_bound) as u128);
+        combined = combined + *value * (coefficient + coefficient * *gamma * shift);
+        coefficient = coefficient * *gamma * *gamma;
+    }
+
+    combined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{low_degree_extend, Field, FieldElement, HashFunction, Polynomial};
+
+    fn batch(field: Field, domain: &EvaluationDomain<FieldElement>) -> Vec<Vec<FieldElement>> {
+        [8, 4, 2, 8]
+            .iter()
+            .enumerate()
+            .map(|(i, &length)| {
+                let coeffs = (0..length)
+                    .map(|c| FieldElement::new((c * 13 + i as i128 * 7 + 1) % 97, field))
+                    .collect();
+                Polynomial::new(coeffs).evaluate_coset(domain)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn batch_accepts_codewords_of_different_degrees() {
+        let field = Field::new(97);
+        let domain = EvaluationDomain::from_size(32, field.generator()).unwrap();
+        let params = FriParams::new(8, 4, 2, 4, 0, 0, HashFunction::Sha256).unwrap();
+        let degree_bounds = [8, 4, 2, 8];
+
+        let proof = batch_prove(batch(field, &domain), &degree_bounds, &domain, &params).unwrap();
+        let received = BatchedFriProof::from_bytes(&proof.to_bytes()).unwrap();
+
+        assert!(batch_verify(&received, &degree_bounds, &domain, &params).is_ok());
+        assert!(received
+            .openings
+            .iter()
+            .all(|opening| opening.evaluations.len() == 4));
+    }
+
+    #[test]
+    fn batch_verifies_with_deep_sampling() {
+        let field = Field::new(97);
+        let domain = EvaluationDomain::from_size(32, field.generator()).unwrap();
+        let params = FriParams::new(8, 4, 2, 4, 0, 0, HashFunction::Sha256)
+            .unwrap()
+            .with_deep_sampling();
+        let degree_bounds = [8, 4, 2, 8];
+
+        let proof = batch_prove(batch(field, &domain), &degree_bounds, &domain, &params).unwrap();
+        let received = BatchedFriProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(received.fri_proof.deep.is_some());
+        assert!(batch_verify(&received, &degree_bounds, &domain, &params).is_ok());
+
+        let mut tampered = proof;
+        tampered.openings[0].evaluations[1][0] =
+            tampered.openings[0].evaluations[1][0] + field.one();
+        assert!(batch_verify(&tampered, &degree_bounds, &domain, &params).is_err());
+    }
+
+    #[test]
+    fn batch_rejects_a_codeword_above_its_degree_bound() {
+        let field = Field::new(97);
+        let domain = EvaluationDomain::from_size(32, field.generator()).unwrap();
+        let params = FriParams::new(8, 4, 2, 8, 0, 0, HashFunction::Sha256).unwrap();
+
+        // the third codeword has degree 1, claimed below 1; the combination then exceeds the bound of 8
+        let degree_bounds = [8, 4, 1, 8];
+        let proof = batch_prove(batch(field, &domain), &degree_bounds, &domain, &params).unwrap();
+        assert!(batch_verify(&proof, &degree_bounds, &domain, &params).is_err());
+
+        // a codeword far from any low-degree polynomial
+        let mut codewords = batch(field, &domain);
+        codewords[1] = (0..32)
+            .map(|i| FieldElement::new((i * i * 31 + 7 * i + 3) % 97, field))
+            .collect();
+        let degree_bounds = [8, 4, 2, 8];
+        let proof = batch_prove(codewords, &degree_bounds, &domain, &params).unwrap();
+        assert!(batch_verify(&proof, &degree_bounds, &domain, &params).is_err());
+    }
+
+    #[test]
+    fn batch_rejects_tampered_opening() {
+        let field = Field::new(97);
+        let domain = EvaluationDomain::from_size(32, field.generator()).unwrap();
+        let params = FriParams::new(8, 4, 2, 4, 0, 0, HashFunction::Sha256).unwrap();
+        let degree_bounds = [8, 4, 2, 8];
+        let proof = batch_prove(batch(field, &domain), &degree_bounds, &domain, &params).unwrap();
+
+        let mut tampered = proof.clone();
+        tampered.openings[0].evaluations[2][1] =
+            tampered.openings[0].evaluations[2][1] + field.one();
+        assert_eq!(
+            batch_verify(&tampered, &degree_bounds, &domain, &params),
+            Err(VerificationError::OpeningAuthentication { query_index: 0 })
+        );
+
+        // an opened value of another field, which the Merkle proof does not bind
+        let mut foreign = proof.clone();
+        foreign.openings[1].evaluations[0][0].field = Field::new(193);
+        assert_eq!(
+            batch_verify(&foreign, &degree_bounds, &domain, &params),
+            Err(VerificationError::NonReducedElement)
+        );
+
+        let mut truncated = proof;
+        truncated.openings.pop();
+        assert!(batch_verify(&truncated, &degree_bounds, &domain, &params).is_err());
+    }
+
+    #[test]
+    fn batch_inputs_are_validated() {
+        let field = Field::new(97);
+        let poly = Polynomial::new((1..9).map(|c| FieldElement::new(c, field)).collect());
+        let (domain, codeword) = low_degree_extend(&poly, 4, field.generator()).unwrap();
+        let params = FriParams::new(8, 4, 2, 4, 0, 0, HashFunction::Sha256).unwrap();
+
+        assert_eq!(
+            batch_prove(vec![codeword.clone()], &[8, 8], &domain, &params).unwrap_err(),
+            FriError::BatchSizeMismatch {
+                codewords: 1,
+                degree_bounds: 2
+            }
+        );
+        assert_eq!(
+            batch_prove(vec![], &[], &domain, &params).unwrap_err(),
+            FriError::BatchSizeMismatch {
+                codewords: 0,
+                degree_bounds: 0
+            }
+        );
+        assert_eq!(
+            batch_prove(vec![codeword.clone()], &[16], &domain, &params).unwrap_err(),
+            FriError::InvalidDegreeBound {
+                degree_bound: 16,
+                domain_size: 32
+            }
+        );
+        assert_eq!(
+            batch_prove(vec![codeword[..16].to_vec()], &[8], &domain, &params).unwrap_err(),
+            FriError::CodewordLengthMismatch {
+                expected: 32,
+                found: 16
+            }
+        );
+    }
+}
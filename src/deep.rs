@@ -0,0 +1,286 @@
+use crate::{batch_inverse, EvaluationDomain, Polynomial, PrimeField, ProofStream};
+use crate::{check_reduced, verify_auth_path, VerificationError};
+use crate::{degree_adjusted_combination, FriDecommitment, FriLayer, FriParams, Proof};
+use serde::{Deserialize, Serialize};
+
+// Transcript labels of the DEEP step, absorbed before the FRI commit phase on the quotient.
+const DEEP_ROOT_LABEL: &str = "fri_deep_root";
+const DEEP_POINT_LABEL: &str = "fri_deep_point";
+const DEEP_EVALUATION_LABEL: &str = "fri_deep_evaluation";
+const DEEP_GAMMA_LABEL: &str = "fri_deep_gamma";
+
+/// The `CommitmentOpening` struct holds, for one query, the coset of committed evaluations
+/// the first FRI layer is derived from and its authentication path in the commitment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitmentOpening<F> {
+    pub auth_path: Option<Proof<Vec<F>>>,
+    pub evaluations: Vec<F>,
+}
+
+/// The `DeepProof` struct holds what the prover sends for the DEEP step of FRI: the commitment to the
+/// original codeword, its evaluation at the out-of-domain point, and one opening of it per query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepProof<F> {
+    pub root: Vec<u8>,     // Merkle root over the cosets of the original codeword.
+    pub ood_evaluation: F, // Claimed value of the codeword's polynomial at the out-of-domain point.
+    pub openings: Vec<CommitmentOpening<F>>, // One opening of the original codeword per query.
+}
+
+impl<F: PrimeField> CommitmentOpening<F> {
+    // Checks that the opening holds one coset of `params.folding_factor()` values, authenticated at
+    // `leaf_index` of the tree with root `root` and `leaf_count` leaves, and reduced in the field of `field`.
+    pub fn verify(
+        &self,
+        root: &[u8],
+        leaf_index: usize,
+        leaf_count: usize,
+        params: &FriParams,
+        field: &F,
+        query_index: usize, // Position of the query, reported in the error.
+    ) -> Result<(), VerificationError> {
+        let authenticated = self.evaluations.len() == params.folding_factor()
+            && verify_auth_path(
+                &self.auth_path,
+                params.hash().algorithm(),
+                root,
+                &self.evaluations,
+                leaf_index,
+                leaf_count,
+            );
+        if !authenticated {
+            return Err(VerificationError::OpeningAuthentication { query_index });
+        }
+
+        check_reduced(&self.evaluations, field)
+    }
+}
+
+// Absorbs the commitment to the original codeword and samples the out-of-domain point from the transcript.
+// Points of the domain are rejected and sampled again, as the quotient by `x - z` would not be defined there.
+pub fn sample_ood_point<F: PrimeField>(
+    transcript: &mut ProofStream,
+    root: &[u8],
+    domain: &EvaluationDomain<F>,
+) -> F {
+    transcript.absorb(DEEP_ROOT_LABEL, root);
+    loop {
+        let z = transcript.squeeze_field_element(DEEP_POINT_LABEL, &domain.generator);
+        if !domain.contains(&z) {
+            return z;
+        }
+    }
+}
+
+// Absorbs the claimed evaluation at the out-of-domain point and squeezes the challenge lifting the quotient.
+pub fn absorb_ood_evaluation<F: PrimeField>(
+    transcript: &mut ProofStream,
+    ood_evaluation: &F,
+    field: &F,
+) -> F {
+    transcript.absorb(DEEP_EVALUATION_LABEL, &ood_evaluation.to_bytes());
+
+    transcript.squeeze_field_element(DEEP_GAMMA_LABEL, field)
+}
+
+// Runs the prover side of the DEEP step on a committed codeword `f`: samples the out-of-domain point `z`,
+// evaluates the polynomial interpolating `f` there and returns that value `y` along with the codeword
+// of the DEEP quotient `(f(x) - y) / (x - z)`, lifted to `params.degree_bound()`, on which FRI then runs.
+pub fn deep_quotient<F: PrimeField>(
+    layer: &FriLayer<F>,          // The committed original codeword.
+    transcript: &mut ProofStream, // Transcript of the protocol, before the FRI commit phase.
+    params: &FriParams,           // Public parameters, giving the degree bound.
+) -> (F, Vec<F>) {
+    let domain = &layer.domain;
+    let z = sample_ood_point(transcript, layer.merkle_tree.root_hash(), domain);
+    let ood_evaluation = Polynomial::interpolate(domain, &layer.evaluations).evaluate(z);
+    let gamma = absorb_ood_evaluation(transcript, &ood_evaluation, &domain.generator);
+
+    let quotient = Quotient {
+        points: vec![z],
+        values: vec![ood_evaluation],
+        gamma,
+        degree_bound: params.degree_bound(),
+    };
+
+    (
+        ood_evaluation,
+        quotient.codeword(&layer.evaluations, domain),
+    )
+}
+
+/// The `Quotient` struct describes the combination of the quotients `(p(x) - y_i) / (x - z_i)` of a
+/// polynomial `p` claimed to take the values `y_i` at the points `z_i`. Each quotient has degree below
+/// `degree_bound - 1` when the claim holds and is lifted to `degree_bound` by `degree_adjusted_combination`,
+/// with the challenge `gamma`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quotient<F> {
+    pub points: Vec<F>,      // Points `z_i`, outside the evaluation domain.
+    pub values: Vec<F>,      // Claimed values `y_i = p(z_i)`.
+    pub gamma: F,            // Challenge combining the quotients.
+    pub degree_bound: usize, // Degree bound of `p` and of the combination.
+}
+
+impl<F: PrimeField> Quotient<F> {
+    // Evaluates the combination over `domain` from the codeword of `p`.
+    // All the denominators are inverted at once, so the points must lie outside the domain.
+    pub fn codeword(&self, evaluations: &[F], domain: &EvaluationDomain<F>) -> Vec<F> {
+        let xs = domain.elements();
+        let denominators: Vec<F> = self
+            .points
+            .iter()
+            .flat_map(|z| xs.iter().map(move |x| *x - *z))
+            .collect();
+        let inverses = batch_inverse(&denominators);
+
+        xs.iter()
+            .enumerate()
+            .map(|(position, x)| {
+                let quotients: Vec<F> = self
+                    .values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, y)| (evaluations[position] - *y) * inverses[i * xs.len() + position])
+                    .collect();
+                self.combine(&quotients, *x)
+            })
+            .collect()
+    }
+
+    // Evaluates the combination at a single point `x`, from the value `p(x)`.
+    pub fn evaluate(&self, value: F, x: F) -> F {
+        let quotients: Vec<F> = self
+            .points
+            .iter()
+            .zip(self.values.iter())
+            .map(|(z, y)| (value - *y) / (x - *z))
+            .collect();
+
+        self.combine(&quotients, x)
+    }
+
+    // Lifts and combines the quotients evaluated at `x`.
+    fn combine(&self, quotients: &[F], x: F) -> F {
+        let degree_bounds = vec![self.degree_bound - 1; quotients.len()];
+
+        degree_adjusted_combination(quotients, x, &self.gamma, &degree_bounds, self.degree_bound)
+    }
+}
+
+// Opens a committed codeword at the leaves opened in the first FRI layer by `decommitments`.
+pub fn open_codeword<F: PrimeField>(
+    layer: &FriLayer<F>,
+    decommitments: &[FriDecommitment<F>],
+) -> Vec<CommitmentOpening<F>> {
+    let leaf_count = layer.domain.size / layer.folding_factor;
+
+    decommitments
+        .iter()
+        .map(|decommitment| {
+            let leaf_index = decommitment.layers_auth_paths[0]
+                .as_ref()
+                .map(|path| path.index)
+                .expect("the first layer is always opened");
+            CommitmentOpening {
+                auth_path: layer.merkle_tree.gen_nth_proof(leaf_index),
+                evaluations: (0..layer.folding_factor)
+                    .map(|t| layer.evaluations[leaf_index + t * leaf_count])
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+// Checks the openings of a committed codeword against `root` at the query positions, and that the quotients
+// recomputed from them are the values `cosets` opened in the codeword FRI ran on.
+// The cosets must already have been checked by the FRI verifier.
+pub fn verify_quotient_openings<F: PrimeField>(
+    root: &[u8],                       // Commitment to the codeword of `p`.
+    openings: &[CommitmentOpening<F>], // One opening of the codeword per query.
+    cosets: &[&[F]],                   // Opened cosets of the quotient, one per query.
+    query_indices: &[usize], // Query indices into the domain, as returned by the FRI verifier.
+    quotient: &Quotient<F>,  // Claimed evaluations of `p` and the combination challenge.
+    domain: &EvaluationDomain<F>, // Domain of the codeword.
+    params: &FriParams,      // Public parameters of the FRI instance on the quotient.
+) -> Result<(), VerificationError> {
+    if openings.len() != query_indices.len() {
+        return Err(VerificationError::QueryCountMismatch {
+            expected: query_indices.len(),
+            found: openings.len(),
+        });
+    }
+
+    let folding_factor = params.folding_factor();
+    let leaf_count = domain.size / folding_factor;
+    for (query_index, ((opening, coset), &index)) in openings
+        .iter()
+        .zip(cosets.iter())
+        .zip(query_indices.iter())
+        .enumerate()
+    {
+        let leaf_index = index % leaf_count;
+        opening.verify(
+            root,
+            leaf_index,
+            leaf_count,
+            params,
+            &domain.generator,
+            query_index,
+        )?;
+
+        // the quotient of the opened values must be what FRI ran on
+        for (t, value) in opening.evaluations.iter().enumerate() {
+            let x = domain.element(leaf_index + t * leaf_count);
+            if coset[t] != quotient.evaluate(*value, x) {
+                return Err(VerificationError::FirstLayerMismatch { query_index });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Field, FieldElement, HashFunction};
+
+    #[test]
+    fn deep_quotient_has_the_degree_bound_only_for_the_right_value() {
+        let field = Field::new(97);
+        let coeffs = [3, 1, 4, 1, 5, 9, 2, 6];
+        let poly = Polynomial::new(
+            coeffs
+                .iter()
+                .map(|&c| FieldElement::new(c, field))
+                .collect(),
+        );
+        let domain = EvaluationDomain::from_size(32, field.generator()).unwrap();
+        let params = FriParams::new(8, 4, 2, 4, 0, 0, HashFunction::Sha256).unwrap();
+        let layer = FriLayer::new(&poly, domain, &params);
+
+        let (ood_evaluation, quotient) = deep_quotient(&layer, &mut ProofStream::new(), &params);
+        let mut interpolated = Polynomial::interpolate(&domain, &quotient);
+        interpolated.normalize();
+        assert!(interpolated.coeffs.len() <= params.degree_bound());
+
+        // replaying the transcript gives the same point, where the claimed value is the polynomial's
+        let mut transcript = ProofStream::new();
+        let z = sample_ood_point(&mut transcript, layer.merkle_tree.root_hash(), &domain);
+        assert!(!domain.contains(&z));
+        assert_eq!(ood_evaluation, poly.evaluate(z));
+
+        // any other value leaves a pole at z, which no polynomial of the degree bound matches
+        let gamma = absorb_ood_evaluation(&mut transcript, &ood_evaluation, &field.one());
+        let wrong = ood_evaluation + field.one();
+        let quotient = Quotient {
+            points: vec![z],
+            values: vec![wrong],
+            gamma,
+            degree_bound: params.degree_bound(),
+        };
+        let codeword = quotient.codeword(&layer.evaluations, &domain);
+        let mut interpolated = Polynomial::interpolate(&domain, &codeword);
+        interpolated.normalize();
+        assert!(interpolated.coeffs.len() > params.degree_bound());
+    }
+}
@@ -0,0 +1,242 @@
+use crate::{factorize, Field, FieldElement, FriError, PrimeField};
+use serde::{Deserialize, Serialize};
+
+/// The `EvaluationDomain` struct describes the multiplicative coset `offset * <generator>`
+/// over which a FRI layer is evaluated. The element at index `i` is `offset * generator^i`,
+/// so `x` and `-x` sit `size / 2` positions apart and squaring halves the domain.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct EvaluationDomain<F> {
+    pub size: usize,  // Number of elements in the domain.
+    pub generator: F, // Generator of the subgroup, of exact order `size`.
+    pub offset: F,    // Coset offset, one for the subgroup itself.
+}
+
+impl<F: PrimeField> EvaluationDomain<F> {
+    // Creates the coset `offset * <generator>`, checking that the generator has exact order `size`.
+    pub fn new(size: usize, generator: F, offset: F) -> Result<Self, FriError> {
+        if size == 0 {
+            return Err(FriError::EmptyDomain);
+        }
+        if !generator.same_field(&offset) {
+            return Err(FriError::FieldMismatch);
+        }
+        if offset.is_zero() {
+            return Err(FriError::ZeroOffset);
+        }
+        if !has_exact_order(generator, size) {
+            return Err(FriError::InvalidGeneratorOrder { size });
+        }
+
+        Ok(Self {
+            size,
+            generator,
+            offset,
+        })
+    }
+
+    // Creates the coset of size `size` shifted by `offset`, using the field's primitive root of unity
+    // of order `size` as generator. Fails if `size` does not divide `p - 1`.
+    pub fn from_size(size: usize, offset: F) -> Result<Self, FriError> {
+        let generator = offset.primitive_nth_root(size as u128)?;
+
+        Self::new(size, generator, offset)
+    }
+
+    // Creates the subgroup generated by `generator`, i.e. a domain without coset offset.
+    pub fn subgroup(size: usize, generator: F) -> Result<Self, FriError> {
+        Self::new(size, generator, generator.one())
+    }
+
+    // Returns the element at position `i`, which is `offset * generator^i`.
+    pub fn element(&self, i: usize) -> F {
+        self.offset * self.generator.pow((i % self.size) as u128)
+    }
+
+    // Returns an iterator over the elements of the domain, in index order.
+    pub fn iter(&self) -> DomainIter<F> {
+        DomainIter {
+            current: self.offset,
+            generator: self.generator,
+            remaining: self.size,
+        }
+    }
+
+    // Collects the elements of the domain into a vector.
+    pub fn elements(&self) -> Vec<F> {
+        self.iter().collect()
+    }
+
+    // Returns whether `x` belongs to the coset, which is when `x^size = offset^size`.
+    pub fn contains(&self, x: &F) -> bool {
+        x.same_field(&self.generator)
+            && x.pow(self.size as u128) == self.offset.pow(self.size as u128)
+    }
+
+    // Returns the domain of the next FRI layer: squaring maps `x` and `-x` to the same point,
+    // so the result is the coset `offset^2 * <generator^2>` of half the size.
+    pub fn square(&self) -> Self {
+        self.fold(2)
+    }
+
+    // Returns the domain of the next FRI layer when folding by `factor`: raising to the power `factor`
+    // maps the `factor` points `x * z^t` to the same point, giving the coset
+    // `offset^factor * <generator^factor>` of size `size / factor`.
+    pub fn fold(&self, factor: usize) -> Self {
+        assert!(
+            factor > 0 && self.size.is_multiple_of(factor),
+            "cannot fold a domain of size {} by {}",
+            self.size,
+            factor
+        );
+
+        Self {
+            size: self.size / factor,
+            generator: self.generator.pow(factor as u128),
+            offset: self.offset.pow(factor as u128),
+        }
+    }
+}
+
+// Domains over `FieldElement` can name their `Field`, whose modulus is only known at runtime.
+impl EvaluationDomain<FieldElement> {
+    // Returns the field the domain lives in.
+    pub fn field(&self) -> Field {
+        self.generator.field
+    }
+}
+
+/// Iterator over the elements of an `EvaluationDomain`, computed by repeated multiplication.
+pub struct DomainIter<F> {
+    current: F,
+    generator: F,
+    remaining: usize,
+}
+
+impl<F: PrimeField> Iterator for DomainIter<F> {
+    type Item = F;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let element = self.current;
+        self.current = self.current * self.generator;
+        self.remaining -= 1;
+
+        Some(element)
+    }
+}
+
+impl<F: PrimeField> IntoIterator for &EvaluationDomain<F> {
+    type Item = F;
+    type IntoIter = DomainIter<F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Checks that `generator^size == 1` and that no proper divisor of `size` already gives one.
+// It is enough to test `size / q` for every prime factor `q` of `size`.
+fn has_exact_order<F: PrimeField>(generator: F, size: usize) -> bool {
+    let one = generator.one();
+    let size = size as u128;
+
+    generator.pow(size) == one
+        && factorize(size)
+            .iter()
+            .all(|(q, _)| generator.pow(size / q) != one)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn domain_elements_are_powers_of_the_generator() {
+        let field = Field::new(97);
+        let g = FieldElement::new(28, field);
+        let domain = EvaluationDomain::subgroup(32, g).unwrap();
+
+        let elements = domain.elements();
+        assert_eq!(elements.len(), 32);
+        for (i, x) in elements.iter().enumerate() {
+            assert_eq!(*x, g.pow(i as u128));
+            assert_eq!(*x, domain.element(i));
+        }
+
+        // x and -x are half a domain apart
+        assert_eq!(domain.element(3), -domain.element(3 + 16));
+    }
+
+    #[test]
+    fn domain_square_halves_the_coset() {
+        let field = Field::new(97);
+        let g = FieldElement::new(28, field);
+        let offset = FieldElement::new(5, field);
+        let domain = EvaluationDomain::new(32, g, offset).unwrap();
+        let squared = domain.square();
+
+        assert_eq!(squared.size, 16);
+        for i in 0..squared.size {
+            assert_eq!(squared.element(i), domain.element(i) * domain.element(i));
+        }
+    }
+
+    #[test]
+    fn domain_contains_exactly_its_elements() {
+        let field = Field::new(97);
+        let domain = EvaluationDomain::from_size(32, field.generator()).unwrap();
+        let elements = domain.elements();
+
+        for n in 0..97 {
+            let x = FieldElement::new(n, field);
+            assert_eq!(domain.contains(&x), elements.contains(&x));
+        }
+    }
+
+    #[test]
+    fn domain_rejects_wrong_generator_order() {
+        let field = Field::new(97);
+        let one = field.one();
+
+        // 28 has order 32, not 16 or 64
+        let g = FieldElement::new(28, field);
+        assert_eq!(
+            EvaluationDomain::subgroup(16, g),
+            Err(FriError::InvalidGeneratorOrder { size: 16 })
+        );
+        assert_eq!(
+            EvaluationDomain::subgroup(64, g),
+            Err(FriError::InvalidGeneratorOrder { size: 64 })
+        );
+
+        // 35 has order 3 modulo 97, so it cannot generate a domain of size 6
+        let g = FieldElement::new(35, field);
+        assert!(EvaluationDomain::subgroup(3, g).is_ok());
+        assert!(EvaluationDomain::subgroup(6, g).is_err());
+
+        assert_eq!(
+            EvaluationDomain::new(32, FieldElement::new(28, field), field.zero()),
+            Err(FriError::ZeroOffset)
+        );
+        assert!(EvaluationDomain::subgroup(1, one).is_ok());
+    }
+
+    #[test]
+    fn domain_from_size_uses_a_primitive_root() {
+        let field = Field::new(998244353);
+        let offset = field.generator();
+
+        for log_size in [1, 4, 10, 23] {
+            let domain = EvaluationDomain::from_size(1 << log_size, offset).unwrap();
+            assert_eq!(domain.size, 1 << log_size);
+        }
+
+        assert_eq!(
+            EvaluationDomain::from_size(1 << 24, offset),
+            Err(FriError::NoRootOfUnity { order: 1 << 24 })
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use std::fmt;
+
+/// The `FriError` enum lists the ways FRI inputs can be rejected
+/// before any commitment is computed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FriError {
+    // The domain size must be at least one.
+    EmptyDomain,
+    // The generator does not have exact multiplicative order equal to the domain size.
+    InvalidGeneratorOrder {
+        size: usize,
+    },
+    // A coset offset of zero would collapse the whole domain to zero.
+    ZeroOffset,
+    // The generator and the offset do not belong to the same field.
+    FieldMismatch,
+    // The field has no element of this multiplicative order, as it does not divide `p - 1`.
+    NoRootOfUnity {
+        order: u128,
+    },
+    // The blowup factor, the inverse of the code rate, must be a power of two of at least 2.
+    InvalidBlowup {
+        blowup: usize,
+    },
+    // The folding factor must be a power of two of at least 2.
+    InvalidFoldingFactor {
+        folding_factor: usize,
+    },
+    // The codeword must hold exactly one value per domain element.
+    CodewordLengthMismatch {
+        expected: usize,
+        found: usize,
+    },
+    // The degree bound must be positive, below the domain size, and the domain must fold down to it.
+    InvalidDegreeBound {
+        degree_bound: usize,
+        domain_size: usize,
+    },
+    // Folding the degree bound cannot reach a final polynomial of at most this degree.
+    InvalidFinalDegree {
+        final_poly_max_degree: usize,
+        degree_bound: usize,
+        folding_factor: usize,
+    },
+    // At least one query is needed, and no more than the number of positions to open.
+    InvalidNumberOfQueries {
+        number_of_queries: usize,
+        domain_size: usize,
+    },
+    // The proof-of-work would be too expensive for the prover.
+    InvalidGrindingBits {
+        grinding_bits: u32,
+        max: u32,
+    },
+    // The evaluation domain does not have the size required by the parameters.
+    DomainSizeMismatch {
+        expected: usize,
+        found: usize,
+    },
+    // A batch needs at least one codeword and exactly one degree bound per codeword.
+    BatchSizeMismatch {
+        codewords: usize,
+        degree_bounds: usize,
+    },
+    // The committed polynomial must have a degree below the degree bound of the parameters.
+    PolynomialDegreeTooLarge {
+        degree: usize,
+        degree_bound: usize,
+    },
+    // An opening needs at least one evaluation point.
+    NoOpeningPoints,
+    // The quotient by `x - z` is undefined on the domain when `z` belongs to it.
+    OpeningPointInDomain {
+        index: usize,
+    },
+    // `X^degree - W` is reducible for every `W` of the base field, as `degree` does not divide `p - 1`.
+    NoIrreducibleBinomial {
+        degree: usize,
+    },
+    // The parameters commit a single layer, so nothing is left to fold after the first fold.
+    SingleLayer {
+        degree_bound: usize,
+        folding_factor: usize,
+    },
+    // The protocol does not run FRI on a DEEP quotient, so the parameters must not ask for DEEP sampling.
+    DeepSamplingUnsupported,
+}
+
+impl fmt::Display for FriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FriError::EmptyDomain => write!(f, "the evaluation domain must not be empty"),
+            FriError::InvalidGeneratorOrder { size } => {
+                write!(f, "the generator does not have exact order {}", size)
+            }
+            FriError::ZeroOffset => write!(f, "the coset offset must be non-zero"),
+            FriError::FieldMismatch => {
+                write!(f, "the generator and the offset belong to different fields")
+            }
+            FriError::NoRootOfUnity { order } => {
+                write!(
+                    f,
+                    "the field has no primitive root of unity of order {}",
+                    order
+                )
+            }
+            FriError::InvalidBlowup { blowup } => {
+                write!(
+                    f,
+                    "the blowup factor {} is not a power of two of at least 2",
+                    blowup
+                )
+            }
+            FriError::InvalidFoldingFactor { folding_factor } => {
+                write!(
+                    f,
+                    "the folding factor {} is not a power of two of at least 2",
+                    folding_factor
+                )
+            }
+            FriError::CodewordLengthMismatch { expected, found } => {
+                write!(
+                    f,
+                    "expected a codeword of {} values, found {}",
+                    expected, found
+                )
+            }
+            FriError::InvalidDegreeBound {
+                degree_bound,
+                domain_size,
+            } => {
+                write!(
+                    f,
+                    "the degree bound {} is not supported over a domain of size {}",
+                    degree_bound, domain_size
+                )
+            }
+            FriError::InvalidFinalDegree {
+                final_poly_max_degree,
+                degree_bound,
+                folding_factor,
+            } => {
+                write!(
+                    f,
+                    "folding the degree bound {} by {} cannot reach a final polynomial of degree at most {}",
+                    degree_bound, folding_factor, final_poly_max_degree
+                )
+            }
+            FriError::InvalidNumberOfQueries {
+                number_of_queries,
+                domain_size,
+            } => {
+                write!(
+                    f,
+                    "{} queries is not between 1 and the domain size {}",
+                    number_of_queries, domain_size
+                )
+            }
+            FriError::InvalidGrindingBits { grinding_bits, max } => {
+                write!(
+                    f,
+                    "{} grinding bits exceeds the maximum of {}",
+                    grinding_bits, max
+                )
+            }
+            FriError::DomainSizeMismatch { expected, found } => {
+                write!(
+                    f,
+                    "expected an evaluation domain of size {}, found {}",
+                    expected, found
+                )
+            }
+            FriError::BatchSizeMismatch {
+                codewords,
+                degree_bounds,
+            } => {
+                write!(
+                    f,
+                    "a batch of {} codewords needs as many degree bounds, found {}",
+                    codewords, degree_bounds
+                )
+            }
+            FriError::PolynomialDegreeTooLarge {
+                degree,
+                degree_bound,
+            } => {
+                write!(
+                    f,
+                    "the polynomial has degree {}, not below the degree bound {}",
+                    degree, degree_bound
+                )
+            }
+            FriError::NoOpeningPoints => write!(f, "an opening needs at least one point"),
+            FriError::OpeningPointInDomain { index } => {
+                write!(
+                    f,
+                    "the opening point at index {} belongs to the evaluation domain",
+                    index
+                )
+            }
+            FriError::NoIrreducibleBinomial { degree } => {
+                write!(
+                    f,
+                    "the base field has no irreducible binomial of degree {}",
+                    degree
+                )
+            }
+            FriError::SingleLayer {
+                degree_bound,
+                folding_factor,
+            } => {
+                write!(
+                    f,
+                    "folding the degree bound {} by {} once already reaches the final polynomial",
+                    degree_bound, folding_factor
+                )
+            }
+            FriError::DeepSamplingUnsupported => {
+                write!(f, "DEEP sampling is not supported by this protocol")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FriError {}
+
+/// The `VerificationError` enum lists the reasons a verifier rejects a proof.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationError {
+    // The public inputs of the verifier are invalid, whatever the proof.
+    InvalidInput(FriError),
+    // The proof does not hold one Merkle root per layer.
+    LayerCountMismatch { expected: usize, found: usize },
+    // The proof holds a DEEP step exactly when the parameters enable DEEP sampling.
+    DeepStepMismatch,
+    // A field element of the proof or of the claim is not a reduced element of the domain's field.
+    NonReducedElement,
+    // The final polynomial is not encoded the way the prover sends it.
+    NonCanonicalFinalPolynomial { max_coefficients: usize },
+    // The proof-of-work nonce does not hash to enough leading zeros.
+    ProofOfWork,
+    // The proof does not hold one opening per query.
+    QueryCountMismatch { expected: usize, found: usize },
+    // A claim needs exactly one value per opening point.
+    EvaluationCountMismatch { expected: usize, found: usize },
+    // The decommitment does not open one coset of `folding_factor` values in every layer.
+    MalformedDecommitment { query_index: usize },
+    // An opened coset does not authenticate against the Merkle root of its layer.
+    LayerAuthentication { layer: usize, query_index: usize },
+    // An opening of the codewords FRI is derived from does not authenticate against their commitment.
+    OpeningAuthentication { query_index: usize },
+    // The fold of a layer's coset is not the value committed in the next layer.
+    FoldingMismatch { layer: usize, query_index: usize },
+    // The fold of the last layer's coset is not the evaluation of the final polynomial.
+    FinalPolynomialMismatch { query_index: usize },
+    // The first layer is not the one derived from the opened codewords.
+    FirstLayerMismatch { query_index: usize },
+}
+
+impl From<FriError> for VerificationError {
+    fn from(error: FriError) -> Self {
+        VerificationError::InvalidInput(error)
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::InvalidInput(error) => write!(f, "invalid input: {}", error),
+            VerificationError::LayerCountMismatch { expected, found } => {
+                write!(
+                    f,
+                    "expected {} layer commitments, found {}",
+                    expected, found
+                )
+            }
+            VerificationError::DeepStepMismatch => {
+                write!(f, "the DEEP step does not match the parameters")
+            }
+            VerificationError::NonReducedElement => {
+                write!(f, "a field element is not reduced in the domain's field")
+            }
+            VerificationError::NonCanonicalFinalPolynomial { max_coefficients } => {
+                write!(
+                    f,
+                    "the final polynomial is not canonical with at most {} coefficients",
+                    max_coefficients
+                )
+            }
+            VerificationError::ProofOfWork => write!(f, "the proof-of-work check failed"),
+            VerificationError::QueryCountMismatch { expected, found } => {
+                write!(f, "expected {} query openings, found {}", expected, found)
+            }
+            VerificationError::EvaluationCountMismatch { expected, found } => {
+                write!(f, "expected {} claimed values, found {}", expected, found)
+            }
+            VerificationError::MalformedDecommitment { query_index } => {
+                write!(f, "malformed decommitment at query index {}", query_index)
+            }
+            VerificationError::LayerAuthentication { layer, query_index } => {
+                write!(
+                    f,
+                    "Merkle proof verification failed at layer {}, at query index {}",
+                    layer, query_index
+                )
+            }
+            VerificationError::OpeningAuthentication { query_index } => {
+                write!(
+                    f,
+                    "commitment opening verification failed at query index {}",
+                    query_index
+                )
+            }
+            VerificationError::FoldingMismatch { layer, query_index } => {
+                write!(
+                    f,
+                    "folding consistency check failed at layer {}, at query index {}",
+                    layer, query_index
+                )
+            }
+            VerificationError::FinalPolynomialMismatch { query_index } => {
+                write!(
+                    f,
+                    "final polynomial check failed at query index {}",
+                    query_index
+                )
+            }
+            VerificationError::FirstLayerMismatch { query_index } => {
+                write!(
+                    f,
+                    "the first layer does not match the opened codewords at query index {}",
+                    query_index
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
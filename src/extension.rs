@@ -0,0 +1,461 @@
+use crate::{FriError, Hashable, PrimeField};
+use ring::digest::{digest, Context, SHA256};
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// The `ExtensionField` trait is implemented by extensions of a base field `F`, so that protocols can
+/// draw their challenges in the extension while the committed values stay in the base field.
+pub trait ExtensionField<F: PrimeField>: PrimeField {
+    // Returns the base field element `x` embedded in the extension field of `self`.
+    fn lift(&self, x: F) -> Self;
+}
+
+// Returns the smallest element `n >= 2` of the field of `base` that is not a `degree`-th power,
+// which makes `X^degree - n` irreducible for a prime `degree`. Such an element exists iff `degree`
+// divides `p - 1`, and `n` is a `degree`-th power iff `n^((p - 1) / degree) = 1`.
+fn smallest_nonresidue<F: PrimeField>(base: &F, degree: u128) -> Result<F, FriError> {
+    let order = base.group_order();
+    if !order.is_multiple_of(degree) {
+        return Err(FriError::NoIrreducibleBinomial {
+            degree: degree as usize,
+        });
+    }
+
+    let one = base.one();
+    Ok((2..)
+        .map(|n| base.integer(n))
+        .find(|n| n.pow(order / degree) != one)
+        .expect("the field has non-residues when the degree divides p - 1"))
+}
+
+// Maps the squeezed `bytes` to the coefficient `i` of an extension element. Each coefficient is sampled
+// from its own hash of the input, as the base fields consume up to 16 bytes per element.
+fn coefficient_bytes(bytes: &[u8], i: u8) -> Vec<u8> {
+    digest(&SHA256, &[&[i], bytes].concat()).as_ref().to_vec()
+}
+
+/// The `QuadraticExtension` struct is an element `c0 + c1 X` of `F[X] / (X^2 - W)` for a non-square `W`.
+/// Like `FieldElement`, it carries the definition of its field, the non-residue `W`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuadraticExtension<F> {
+    pub coeffs: [F; 2], // Coefficients `c0, c1` of the element, lowest degree first.
+    pub nonresidue: F,  // The non-square `W` defining the extension.
+}
+
+impl<F: PrimeField> QuadraticExtension<F> {
+    // Creates the element `c0 + c1 X` of `F[X] / (X^2 - nonresidue)`, where `nonresidue` must not be a square.
+    pub fn new(coeffs: [F; 2], nonresidue: F) -> Self {
+        Self { coeffs, nonresidue }
+    }
+
+    // Returns the smallest non-square `W >= 2` of the field of `base`, which always exists for an odd prime.
+    pub fn nonresidue(base: &F) -> F {
+        smallest_nonresidue(base, 2).expect("p - 1 is even for an odd prime")
+    }
+
+    // Embeds `x` in the quadratic extension defined by the smallest non-square of its field.
+    pub fn from_base(x: F) -> Self {
+        Self::new([x, x.zero()], Self::nonresidue(&x))
+    }
+}
+
+// Implements the `ExtensionField` trait, the base field being the constant coefficients.
+impl<F: PrimeField> ExtensionField<F> for QuadraticExtension<F> {
+    fn lift(&self, x: F) -> Self {
+        Self::new([x, x.zero()], self.nonresidue)
+    }
+}
+
+// Implements the `PrimeField` trait so FRI can run over `QuadraticExtension`.
+// The evaluation domains stay subgroups of the base field, so the group order, the generator and
+// therefore the roots of unity are the base field's ones.
+impl<F: PrimeField> PrimeField for QuadraticExtension<F> {
+    fn zero(&self) -> Self {
+        self.lift(self.nonresidue.zero())
+    }
+
+    fn one(&self) -> Self {
+        self.lift(self.nonresidue.one())
+    }
+
+    fn integer(&self, n: u64) -> Self {
+        self.lift(self.nonresidue.integer(n))
+    }
+
+    // The inverse of `a + b X` is `(a - b X) / (a^2 - W b^2)`, where the norm `a^2 - W b^2` is
+    // nonzero for a nonzero element, as `W` is not a square.
+    fn inverse(&self) -> Self {
+        assert!(!self.is_zero(), "cannot invert 0");
+        let [a, b] = self.coeffs;
+        let norm_inverse = (a * a - self.nonresidue * b * b).inverse();
+
+        Self::new([a * norm_inverse, -b * norm_inverse], self.nonresidue)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.coeffs.iter().all(|c| c.is_zero())
+    }
+
+    fn is_reduced(&self) -> bool {
+        self.nonresidue.is_reduced() && self.coeffs.iter().all(|c| c.is_reduced())
+    }
+
+    fn group_order(&self) -> u128 {
+        self.nonresidue.group_order()
+    }
+
+    fn generator(&self) -> Self {
+        self.lift(self.nonresidue.generator())
+    }
+
+    // The encodings of the coefficients, lowest degree first.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.coeffs.iter().flat_map(|c| c.to_bytes()).collect()
+    }
+
+    fn sample(&self, bytes: &[u8]) -> Self {
+        let base = self.nonresidue;
+        let coeffs = [0, 1].map(|i| base.sample(&coefficient_bytes(bytes, i)));
+
+        Self::new(coeffs, self.nonresidue)
+    }
+
+    fn same_field(&self, other: &Self) -> bool {
+        self.nonresidue.same_field(&other.nonresidue) && self.nonresidue == other.nonresidue
+    }
+}
+
+// Implements the `Hashable` trait to make `QuadraticExtension` usable in a Merkle tree.
+impl<F: PrimeField> Hashable for QuadraticExtension<F> {
+    fn update_context(&self, context: &mut Context) {
+        for c in &self.coeffs {
+            c.update_context(context);
+        }
+    }
+}
+
+// Implements the `Add` trait, coefficient by coefficient.
+impl<F: PrimeField> Add for QuadraticExtension<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let [a0, a1] = self.coeffs;
+        let [b0, b1] = rhs.coeffs;
+
+        Self::new([a0 + b0, a1 + b1], self.nonresidue)
+    }
+}
+
+// Implements the `Sub` trait, coefficient by coefficient.
+impl<F: PrimeField> Sub for QuadraticExtension<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let [a0, a1] = self.coeffs;
+        let [b0, b1] = rhs.coeffs;
+
+        Self::new([a0 - b0, a1 - b1], self.nonresidue)
+    }
+}
+
+// Implements the `Mul` trait, reducing the product with `X^2 = W`.
+impl<F: PrimeField> Mul for QuadraticExtension<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let [a0, a1] = self.coeffs;
+        let [b0, b1] = rhs.coeffs;
+
+        Self::new(
+            [a0 * b0 + self.nonresidue * a1 * b1, a0 * b1 + a1 * b0],
+            self.nonresidue,
+        )
+    }
+}
+
+// Implements the `Div` trait for division of `QuadraticExtension` elements.
+impl<F: PrimeField> Div for QuadraticExtension<F> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(!rhs.is_zero(), "divide by 0");
+
+        self * rhs.inverse()
+    }
+}
+
+// Implements the `Neg` trait, coefficient by coefficient.
+impl<F: PrimeField> Neg for QuadraticExtension<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        let [a0, a1] = self.coeffs;
+
+        Self::new([-a0, -a1], self.nonresidue)
+    }
+}
+
+/// The `CubicExtension` struct is an element `c0 + c1 X + c2 X^2` of `F[X] / (X^3 - W)` for a non-cube `W`.
+/// Non-cubes only exist when 3 divides `p - 1`, which holds for Goldilocks, BabyBear and Mersenne31.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CubicExtension<F> {
+    pub coeffs: [F; 3], // Coefficients `c0, c1, c2` of the element, lowest degree first.
+    pub nonresidue: F,  // The non-cube `W` defining the extension.
+}
+
+impl<F: PrimeField> CubicExtension<F> {
+    // Creates the element `c0 + c1 X + c2 X^2` of `F[X] / (X^3 - nonresidue)`, where `nonresidue` must not be a cube.
+    pub fn new(coeffs: [F; 3], nonresidue: F) -> Self {
+        Self { coeffs, nonresidue }
+    }
+
+    // Returns the smallest non-cube `W >= 2` of the field of `base`.
+    // Fails when 3 does not divide `p - 1`, as every element is then a cube and no `X^3 - W` is irreducible.
+    pub fn nonresidue(base: &F) -> Result<F, FriError> {
+        smallest_nonresidue(base, 3)
+    }
+
+    // Embeds `x` in the cubic extension defined by the smallest non-cube of its field.
+    pub fn from_base(x: F) -> Result<Self, FriError> {
+        Ok(Self::new([x, x.zero(), x.zero()], Self::nonresidue(&x)?))
+    }
+}
+
+// Implements the `ExtensionField` trait, the base field being the constant coefficients.
+impl<F: PrimeField> ExtensionField<F> for CubicExtension<F> {
+    fn lift(&self, x: F) -> Self {
+        Self::new([x, x.zero(), x.zero()], self.nonresidue)
+    }
+}
+
+// Implements the `PrimeField` trait so FRI can run over `CubicExtension`.
+// As for `QuadraticExtension`, the group order, the generator and the roots of unity are the base field's.
+impl<F: PrimeField> PrimeField for CubicExtension<F> {
+    fn zero(&self) -> Self {
+        self.lift(self.nonresidue.zero())
+    }
+
+    fn one(&self) -> Self {
+        self.lift(self.nonresidue.one())
+    }
+
+    fn integer(&self, n: u64) -> Self {
+        self.lift(self.nonresidue.integer(n))
+    }
+
+    // The inverse of `a + b X + c X^2` is `(A + B X + C X^2) / N` with `A = a^2 - W b c`, `B = W c^2 - a b`,
+    // `C = b^2 - a c` and the norm `N = a A + W (c B + b C)`, nonzero for a nonzero element as `W` is not a cube.
+    fn inverse(&self) -> Self {
+        assert!(!self.is_zero(), "cannot invert 0");
+        let [a, b, c] = self.coeffs;
+        let w = self.nonresidue;
+        let a_ = a * a - w * b * c;
+        let b_ = w * c * c - a * b;
+        let c_ = b * b - a * c;
+        let norm_inverse = (a * a_ + w * (c * b_ + b * c_)).inverse();
+
+        Self::new([a_ * norm_inverse, b_ * norm_inverse, c_ * norm_inverse], w)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.coeffs.iter().all(|c| c.is_zero())
+    }
+
+    fn is_reduced(&self) -> bool {
+        self.nonresidue.is_reduced() && self.coeffs.iter().all(|c| c.is_reduced())
+    }
+
+    fn group_order(&self) -> u128 {
+        self.nonresidue.group_order()
+    }
+
+    fn generator(&self) -> Self {
+        self.lift(self.nonresidue.generator())
+    }
+
+    // The encodings of the coefficients, lowest degree first.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.coeffs.iter().flat_map(|c| c.to_bytes()).collect()
+    }
+
+    fn sample(&self, bytes: &[u8]) -> Self {
+        let base = self.nonresidue;
+        let coeffs = [0, 1, 2].map(|i| base.sample(&coefficient_bytes(bytes, i)));
+
+        Self::new(coeffs, self.nonresidue)
+    }
+
+    fn same_field(&self, other: &Self) -> bool {
+        self.nonresidue.same_field(&other.nonresidue) && self.nonresidue == other.nonresidue
+    }
+}
+
+// Implements the `Hashable` trait to make `CubicExtension` usable in a Merkle tree.
+impl<F: PrimeField> Hashable for CubicExtension<F> {
+    fn update_context(&self, context: &mut Context) {
+        for c in &self.coeffs {
+            c.update_context(context);
+        }
+    }
+}
+
+// Implements the `Add` trait, coefficient by coefficient.
+impl<F: PrimeField> Add for CubicExtension<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let [a0, a1, a2] = self.coeffs;
+        let [b0, b1, b2] = rhs.coeffs;
+
+        Self::new([a0 + b0, a1 + b1, a2 + b2], self.nonresidue)
+    }
+}
+
+// Implements the `Sub` trait, coefficient by coefficient.
+impl<F: PrimeField> Sub for CubicExtension<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let [a0, a1, a2] = self.coeffs;
+        let [b0, b1, b2] = rhs.coeffs;
+
+        Self::new([a0 - b0, a1 - b1, a2 - b2], self.nonresidue)
+    }
+}
+
+// Implements the `Mul` trait, reducing the product with `X^3 = W`.
+impl<F: PrimeField> Mul for CubicExtension<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let [a0, a1, a2] = self.coeffs;
+        let [b0, b1, b2] = rhs.coeffs;
+        let w = self.nonresidue;
+
+        Self::new(
+            [
+                a0 * b0 + w * (a1 * b2 + a2 * b1),
+                a0 * b1 + a1 * b0 + w * a2 * b2,
+                a0 * b2 + a1 * b1 + a2 * b0,
+            ],
+            w,
+        )
+    }
+}
+
+// Implements the `Div` trait for division of `CubicExtension` elements.
+impl<F: PrimeField> Div for CubicExtension<F> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(!rhs.is_zero(), "divide by 0");
+
+        self * rhs.inverse()
+    }
+}
+
+// Implements the `Neg` trait, coefficient by coefficient.
+impl<F: PrimeField> Neg for CubicExtension<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        let [a0, a1, a2] = self.coeffs;
+
+        Self::new([-a0, -a1, -a2], self.nonresidue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BabyBear, Field, FieldElement, Goldilocks, Polynomial};
+
+    // Multiplies two elements given by their coefficients as polynomials, reduced modulo `X^d - W`.
+    fn reference_product<F: PrimeField>(a: &[F], b: &[F], nonresidue: F) -> Vec<F> {
+        let one = nonresidue.one();
+        let mut modulus = vec![one.zero(); a.len() + 1];
+        modulus[0] = -nonresidue;
+        modulus[a.len()] = one;
+
+        let product = Polynomial::new(a.to_vec()) * Polynomial::new(b.to_vec());
+        let (_, mut remainder) = product.div_rem(&Polynomial::new(modulus));
+        remainder.coeffs.resize(a.len(), one.zero());
+
+        remainder.coeffs
+    }
+
+    // A few elements of the base field of `x`, from pseudo-random integers.
+    fn base_values<F: PrimeField>(x: F, count: usize) -> Vec<F> {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        (0..count)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                x.integer(state)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quadratic_arithmetic_and_inverse() {
+        let field = Field::new(97);
+        let x = QuadraticExtension::from_base(FieldElement::new(1, field));
+        assert_eq!(x.nonresidue, FieldElement::new(5, field));
+        let w = QuadraticExtension::nonresidue(&Goldilocks::new(1));
+        assert_eq!(w, Goldilocks::new(7));
+
+        let values = base_values(Goldilocks::new(1), 40);
+        for chunk in values.chunks(4) {
+            let x = QuadraticExtension::new([chunk[0], chunk[1]], w);
+            let y = QuadraticExtension::new([chunk[2], chunk[3]], w);
+
+            assert_eq!(
+                (x * y).coeffs.to_vec(),
+                reference_product(&x.coeffs, &y.coeffs, w)
+            );
+            assert_eq!(x * x.inverse(), x.one());
+            assert_eq!((x / y) * y, x);
+            assert_eq!(x - y + y, x);
+            assert_eq!(x + (-x), x.zero());
+        }
+    }
+
+    #[test]
+    fn cubic_arithmetic_and_inverse() {
+        let w = CubicExtension::nonresidue(&BabyBear::new(1)).unwrap();
+        let values = base_values(BabyBear::new(1), 60);
+        for chunk in values.chunks(6) {
+            let x = CubicExtension::new([chunk[0], chunk[1], chunk[2]], w);
+            let y = CubicExtension::new([chunk[3], chunk[4], chunk[5]], w);
+
+            assert_eq!(
+                (x * y).coeffs.to_vec(),
+                reference_product(&x.coeffs, &y.coeffs, w)
+            );
+            assert_eq!(x * x.inverse(), x.one());
+            assert_eq!((x / y) * y, x);
+        }
+
+        // every element of a field with 3 not dividing p - 1 is a cube
+        let field = Field::new(5);
+        assert_eq!(
+            CubicExtension::from_base(field.one()).unwrap_err(),
+            FriError::NoIrreducibleBinomial { degree: 3 }
+        );
+    }
+
+    #[test]
+    fn sampling_and_field_checks() {
+        let x = CubicExtension::from_base(Goldilocks::new(3)).unwrap();
+        let challenge = x.sample(&[7; 32]);
+        assert!(challenge.coeffs.iter().all(|c| !c.is_zero()));
+        assert_ne!(challenge.coeffs[0], challenge.coeffs[1]);
+        assert_eq!(challenge.to_bytes().len(), 24);
+        assert!(challenge.is_reduced_in(&x));
+
+        let other = CubicExtension::new(x.coeffs, x.nonresidue + Goldilocks::new(1));
+        assert!(!challenge.is_reduced_in(&other));
+        assert_eq!(x.lift(Goldilocks::new(5)).to_bytes()[8..], [0; 16]);
+    }
+}
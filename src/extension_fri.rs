@@ -0,0 +1,290 @@
+use crate::{
+    coset_ntt, fold_codeword, folded_coset_evaluation, fri_commit, fri_decommit, fri_grind,
+};
+use crate::{fri_query_indices, verify_fri_with_transcript, CommitmentOpening, ExtensionField};
+use crate::{proof_from_bytes, proof_to_bytes, VerificationError};
+use crate::{EvaluationDomain, FriError, FriLayer, FriParams, FriProof, PrimeField, ProofStream};
+use serde::{Deserialize, Serialize};
+
+// Transcript labels of the first layer, absorbed before the FRI commit phase over the extension.
+const EXTENSION_ROOT_LABEL: &str = "fri_extension_root";
+const EXTENSION_ALPHA_LABEL: &str = "fri_extension_alpha";
+
+/// The `ExtensionFriProof` struct proves the proximity of a base field codeword with challenges drawn in
+/// an extension field `E`. The first layer is committed and opened in the base field `F`; its fold by the
+/// first challenge, and every layer after it, are codewords over the extension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionFriProof<F, E> {
+    pub root: Vec<u8>, // Merkle root over the cosets of the base field codeword.
+    pub openings: Vec<CommitmentOpening<F>>, // One opening of the first layer per query.
+    pub fri_proof: FriProof<E>, // Proof of proximity of the folded codeword, over the extension.
+}
+
+impl<F: PrimeField, E: ExtensionField<F>> ExtensionFriProof<F, E> {
+    // Serializes the extension proof with `proof_to_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        proof_to_bytes(self)
+    }
+
+    // Reconstructs the extension proof from the bytes produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        proof_from_bytes(bytes)
+    }
+}
+
+// Returns `domain` as a domain of the extension field of `extension`; the points stay in the base field.
+pub fn lift_domain<F: PrimeField, E: ExtensionField<F>>(
+    domain: &EvaluationDomain<F>,
+    extension: &E,
+) -> EvaluationDomain<E> {
+    EvaluationDomain {
+        size: domain.size,
+        generator: extension.lift(domain.generator),
+        offset: extension.lift(domain.offset),
+    }
+}
+
+// Proves that the base field `codeword` is close to a polynomial of degree below `params.degree_bound()`,
+// drawing every folding challenge in the extension field of `extension`. Over a small base field, this
+// gives each fold the soundness of the larger field.
+// The first layer is committed in the base field and folded with an extension challenge, then FRI runs
+// on the folded codeword over the extension with `params.after_first_fold()`. Parameters committing a
+// single layer are rejected with `SingleLayer`, and DEEP sampling with `DeepSamplingUnsupported`.
+pub fn prove_low_degree_in_extension<F: PrimeField, E: ExtensionField<F>>(
+    codeword: Vec<F>,             // Committed values, one per domain element.
+    domain: &EvaluationDomain<F>, // Domain of the codeword, in the base field.
+    params: &FriParams,           // Public parameters, including the claimed degree bound.
+    extension: &E,                // Any element of the extension field the challenges are drawn in.
+) -> Result<ExtensionFriProof<F, E>, FriError> {
+    if params.deep_sampling() {
+        return Err(FriError::DeepSamplingUnsupported);
+    }
+    if domain.size != params.domain_size() {
+        return Err(FriError::DomainSizeMismatch {
+            expected: params.domain_size(),
+            found: domain.size,
+        });
+    }
+    if codeword.len() != domain.size {
+        return Err(FriError::CodewordLengthMismatch {
+            expected: domain.size,
+            found: codeword.len(),
+        });
+    }
+    let folded_params = params.after_first_fold()?;
+    let folding_factor = params.folding_factor();
+
+    // commit to the first layer in the base field, then fold it with a challenge of the extension
+    let mut transcript = ProofStream::new();
+    let layer = FriLayer::from_codeword(*domain, codeword, params);
+    transcript.absorb(EXTENSION_ROOT_LABEL, layer.merkle_tree.root_hash());
+    let alpha = transcript.squeeze_field_element(EXTENSION_ALPHA_LABEL, extension);
+
+    let lifted_domain = lift_domain(domain, extension);
+    let lifted: Vec<E> = layer
+        .evaluations
+        .iter()
+        .map(|v| extension.lift(*v))
+        .collect();
+    let folded = fold_codeword(&lifted, &lifted_domain, &alpha, folding_factor);
+    let folded_domain = lifted_domain.fold(folding_factor);
+
+    let (final_polynomial, fri_layers) =
+        fri_commit(folded, &mut transcript, &folded_domain, &folded_params);
+    let pow_nonce = fri_grind(&mut transcript, &folded_params);
+    let query_indices = fri_query_indices(&mut transcript, folded_domain.size, &folded_params);
+    let decommitments = fri_decommit(&fri_layers, &query_indices);
+
+    // the value at position `i` of the folded codeword comes from leaf `i` of the first layer
+    let leaf_count = folded_domain.size;
+    let openings = query_indices
+        .iter()
+        .map(|&leaf_index| CommitmentOpening {
+            auth_path: layer.merkle_tree.gen_nth_proof(leaf_index),
+            evaluations: (0..folding_factor)
+                .map(|t| layer.evaluations[leaf_index + t * leaf_count])
+                .collect(),
+        })
+        .collect();
+
+    Ok(ExtensionFriProof {
+        root: layer.merkle_tree.root_hash().clone(),
+        openings,
+        fri_proof: FriProof::new(&fri_layers, final_polynomial, pow_nonce, decommitments),
+    })
+}
+
+// Verifies a proof produced by `prove_low_degree_in_extension` with the same extension field.
+// Beyond the FRI checks over the extension, every query opens the base field layer and folds it with the
+// first challenge, which must give the value opened in the first layer over the extension.
+pub fn verify_fri_in_extension<F: PrimeField, E: ExtensionField<F>>(
+    proof: &ExtensionFriProof<F, E>, // Proof produced by `prove_low_degree_in_extension`.
+    domain: &EvaluationDomain<F>,    // Domain of the codeword, in the base field.
+    params: &FriParams,              // Public parameters the prover committed with.
+    extension: &E, // Any element of the extension field the challenges are drawn in.
+) -> Result<(), VerificationError> {
+    if params.deep_sampling() {
+        return Err(FriError::DeepSamplingUnsupported.into());
+    }
+    if domain.size != params.domain_size() {
+        return Err(FriError::DomainSizeMismatch {
+            expected: params.domain_size(),
+            found: domain.size,
+        }
+        .into());
+    }
+    let folded_params = params.after_first_fold()?;
+    let folding_factor = params.folding_factor();
+
+    // replay the first layer, then FRI over the extension on the same transcript
+    let mut transcript = ProofStream::new();
+    transcript.absorb(EXTENSION_ROOT_LABEL, &proof.root);
+    let alpha = transcript.squeeze_field_element(EXTENSION_ALPHA_LABEL, extension);
+
+    let lifted_domain = lift_domain(domain, extension);
+    let folded_domain = lifted_domain.fold(folding_factor);
+    let query_indices = verify_fri_with_transcript(
+        &proof.fri_proof,
+        &folded_domain,
+        &folded_params,
+        &mut transcript,
+    )?;
+    if proof.openings.len() != query_indices.len() {
+        return Err(VerificationError::QueryCountMismatch {
+            expected: query_indices.len(),
+            found: proof.openings.len(),
+        });
+    }
+
+    let leaf_count = folded_domain.size;
+    let folded_leaf_count = folded_domain.size / folding_factor;
+    let ntt = coset_ntt(&lifted_domain, folding_factor);
+    for (query_index, ((opening, decommitment), &leaf_index)) in proof
+        .openings
+        .iter()
+        .zip(proof.fri_proof.decommitments.iter())
+        .zip(query_indices.iter())
+        .enumerate()
+    {
+        // the values over the extension were checked by the FRI verifier, these are in the base field
+        opening.verify(
+            &proof.root,
+            leaf_index,
+            leaf_count,
+            params,
+            &domain.generator,
+            query_index,
+        )?;
+
+        // fold the opened coset `x * <z>` with the extension challenge
+        let coset = EvaluationDomain {
+            size: folding_factor,
+            generator: lifted_domain.generator.pow(leaf_count as u128),
+            offset: lifted_domain.element(leaf_index),
+        };
+        let lifted: Vec<E> = opening
+            .evaluations
+            .iter()
+            .map(|v| extension.lift(*v))
+            .collect();
+        let expected = folded_coset_evaluation(&coset, &lifted, &alpha, &ntt);
+        if decommitment.layers_evaluations[0][leaf_index / folded_leaf_count] != expected {
+            return Err(VerificationError::FirstLayerMismatch { query_index });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{low_degree_extend, BabyBear, CubicExtension, HashFunction, Polynomial};
+    use crate::{Goldilocks, QuadraticExtension};
+
+    fn params() -> FriParams {
+        FriParams::new(16, 4, 2, 8, 0, 0, HashFunction::Sha256).unwrap()
+    }
+
+    #[test]
+    fn proofs_verify_over_quadratic_and_cubic_extensions() {
+        let poly = Polynomial::new((1..17).map(|c| BabyBear::new(c * 1_000_003)).collect());
+        let (domain, codeword) = low_degree_extend(&poly, 4, BabyBear::GENERATOR).unwrap();
+        let params = params();
+
+        let quadratic = QuadraticExtension::from_base(BabyBear::new(1));
+        let proof =
+            prove_low_degree_in_extension(codeword.clone(), &domain, &params, &quadratic).unwrap();
+        let received = ExtensionFriProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(verify_fri_in_extension(&received, &domain, &params, &quadratic).is_ok());
+        assert_eq!(
+            proof.fri_proof.layer_roots.len(),
+            params.number_layers() - 1
+        );
+
+        let cubic = CubicExtension::from_base(BabyBear::new(1)).unwrap();
+        let proof = prove_low_degree_in_extension(codeword, &domain, &params, &cubic).unwrap();
+        assert!(verify_fri_in_extension(&proof, &domain, &params, &cubic).is_ok());
+    }
+
+    #[test]
+    fn verify_rejects_tampered_proofs_and_far_codewords() {
+        let poly = Polynomial::new((1..17).map(Goldilocks::new).collect());
+        let (domain, codeword) = low_degree_extend(&poly, 4, Goldilocks::GENERATOR).unwrap();
+        let params = params();
+        let extension = QuadraticExtension::from_base(Goldilocks::new(1));
+        let proof = prove_low_degree_in_extension(codeword, &domain, &params, &extension).unwrap();
+
+        let mut tampered = proof.clone();
+        tampered.openings[0].evaluations[0] =
+            tampered.openings[0].evaluations[0] + Goldilocks::new(1);
+        assert!(verify_fri_in_extension(&tampered, &domain, &params, &extension).is_err());
+
+        // the challenges must be drawn in the extension the verifier expects
+        let other = QuadraticExtension::new(extension.coeffs, Goldilocks::new(11));
+        assert!(verify_fri_in_extension(&proof, &domain, &params, &other).is_err());
+
+        let far: Vec<Goldilocks> = (0..64u64)
+            .map(|i| Goldilocks::new(i * i * i + 7 * i))
+            .collect();
+        let proof = prove_low_degree_in_extension(far, &domain, &params, &extension).unwrap();
+        assert!(verify_fri_in_extension(&proof, &domain, &params, &extension).is_err());
+    }
+
+    #[test]
+    fn deep_sampling_and_single_layer_parameters_are_rejected() {
+        let poly = Polynomial::new((1..9).map(BabyBear::new).collect());
+        let (domain, codeword) = low_degree_extend(&poly, 4, BabyBear::GENERATOR).unwrap();
+        let extension = QuadraticExtension::from_base(BabyBear::new(1));
+
+        // a proof for the plain parameters does not verify once DEEP sampling is asked for
+        let params = FriParams::new(8, 4, 2, 8, 0, 0, HashFunction::Sha256).unwrap();
+        let proof =
+            prove_low_degree_in_extension(codeword.clone(), &domain, &params, &extension).unwrap();
+        let deep = params.with_deep_sampling();
+        assert_eq!(
+            prove_low_degree_in_extension(codeword.clone(), &domain, &deep, &extension)
+                .unwrap_err(),
+            FriError::DeepSamplingUnsupported
+        );
+        assert_eq!(
+            verify_fri_in_extension(&proof, &domain, &deep, &extension).unwrap_err(),
+            VerificationError::InvalidInput(FriError::DeepSamplingUnsupported)
+        );
+
+        // folding 8 by 4 once leaves a line, the final polynomial, with no layer to run FRI on
+        let single = FriParams::new(8, 4, 4, 8, 1, 0, HashFunction::Sha256).unwrap();
+        let expected = FriError::SingleLayer {
+            degree_bound: 8,
+            folding_factor: 4,
+        };
+        assert_eq!(
+            prove_low_degree_in_extension(codeword, &domain, &single, &extension).unwrap_err(),
+            expected
+        );
+        assert_eq!(
+            verify_fri_in_extension(&proof, &domain, &single, &extension).unwrap_err(),
+            VerificationError::InvalidInput(expected)
+        );
+    }
+}
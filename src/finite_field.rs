@@ -1,4 +1,4 @@
-use merkle::Hashable;
+use crate::{smallest_generator, Hashable, PrimeField};
 use modulo::Mod;
 use ring::digest::Context;
 use serde::{Deserialize, Serialize};
@@ -32,22 +32,56 @@ impl FieldElement {
 
         FieldElement { num, field }
     }
+}
 
-    // Computes the power of the `FieldElement` using a given exponent.
-    // The result is taken modulo the prime of the field.
-    pub fn pow(&self, exponent: u32) -> Self {
-        let num = self.num.pow(exponent).modulo(self.field.prime);
-        Self {
-            num,
-            field: self.field,
-        }
+// Implements the `PrimeField` trait so FRI can run over `FieldElement`, whose modulus is stored in `field`.
+impl PrimeField for FieldElement {
+    fn zero(&self) -> Self {
+        self.field.zero()
+    }
+
+    fn one(&self) -> Self {
+        self.field.one()
     }
 
-    // Returns the multiplicative inverse of the `FieldElement`.
-    // This is computed using the field's inverse operation.
-    pub fn inverse(&self) -> Self {
+    fn integer(&self, n: u64) -> Self {
+        FieldElement::new(n as i128, self.field)
+    }
+
+    // The extended Euclidean algorithm of `Field::inverse`, faster than the default exponentiation.
+    fn inverse(&self) -> Self {
         self.field.inverse(*self)
     }
+
+    fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    fn is_reduced(&self) -> bool {
+        self.field.contains(self)
+    }
+
+    fn group_order(&self) -> u128 {
+        self.field.group_order()
+    }
+
+    fn generator(&self) -> Self {
+        self.field.generator()
+    }
+
+    // The value as 16 big-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.num.to_be_bytes().to_vec()
+    }
+
+    // 120 bits of the input are reduced modulo the prime, which keeps the bias negligible.
+    fn sample(&self, bytes: &[u8]) -> Self {
+        self.field.sample(bytes[0..15].to_vec())
+    }
+
+    fn same_field(&self, other: &Self) -> bool {
+        self.field.prime == other.field.prime
+    }
 }
 
 // Implements the `Hashable` trait to make `FieldElement` usable in a Merkle tree.
@@ -175,26 +209,28 @@ impl Field {
         }
     }
 
-    // Divides the first `FieldElement` by the second by using the extended Euclidean algorithm.
+    // Divides the first `FieldElement` by the second by multiplying with its inverse.
     pub fn divide(&self, left: FieldElement, right: FieldElement) -> FieldElement {
         assert!(right.num != 0, "divide by 0");
 
-        let (a, _b, _g) = extended_euclidean_algorithm(right.num, self.prime);
-        FieldElement {
-            num: (left.num * a).modulo(self.prime),
-            field: *self,
-        }
+        self.multiply(left, self.inverse(right))
     }
 
     // Returns the multiplicative inverse of a `FieldElement` using the extended Euclidean algorithm.
+    // The Bezout coefficient `s` of `s * a + t * p = 1` is the inverse of `a` modulo `p`.
     pub fn inverse(&self, operand: FieldElement) -> FieldElement {
-        let (a, _b, _c) = extended_euclidean_algorithm(operand.num, self.prime);
+        let (_g, s, _t) = extended_euclidean_algorithm(operand.num, self.prime);
         FieldElement {
-            num: a,
+            num: s.modulo(self.prime),
             field: *self,
         }
     }
 
+    // Returns true when `element` belongs to this field and is reduced, i.e. `0 <= num < prime`.
+    pub fn contains(&self, element: &FieldElement) -> bool {
+        element.field.prime == self.prime && (0..self.prime).contains(&element.num)
+    }
+
     // Returns the negation of a `FieldElement`.
     pub fn negate(&self, operand: FieldElement) -> FieldElement {
         FieldElement {
@@ -203,25 +239,20 @@ impl Field {
         }
     }
 
-    // Returns a generator for the field. In this case, the number 28 is used as the generator.
-    pub fn generator(&self) -> FieldElement {
-        // using 2 as the generator of the field because 2 has high order
-        FieldElement::new(28, *self)
+    // Returns the order of the multiplicative group, `p - 1`.
+    pub fn group_order(&self) -> u128 {
+        (self.prime - 1) as u128
     }
 
-    // Returns the nth primitive root of unity in the field by exponentiating the generator.
-    //NB: didn't use this in the code because it was a very expensive operation
-    pub fn primitive_nth_root(&self, n: i128) -> FieldElement {
-        let mut root = self.generator(); // Start with the generator of the field
-        let mut order = 2;
-
-        // Divide the order of the generator down to n
-        while order != n {
-            root = root.pow(2);
-            order /= 2;
-        }
+    // Returns the prime factorization of the multiplicative group order `p - 1`,
+    // as `(prime, exponent)` pairs in increasing order of the prime.
+    pub fn group_order_factors(&self) -> Vec<(u128, u32)> {
+        factorize(self.group_order())
+    }
 
-        root
+    // Returns a generator of the multiplicative group, the smallest element of order `p - 1`.
+    pub fn generator(&self) -> FieldElement {
+        smallest_generator(&self.one())
     }
 
     // Samples a field element from a byte array by treating the array as an integer
@@ -236,6 +267,147 @@ impl Field {
     }
 }
 
+// Returns the prime factorization of `n` as `(prime, exponent)` pairs in increasing order of the prime.
+// Small factors are removed by trial division, the remaining ones are split with Pollard's rho.
+pub fn factorize(n: u128) -> Vec<(u128, u32)> {
+    let mut primes = vec![];
+    let mut remaining = n;
+
+    for p in [2u128, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+        while remaining.is_multiple_of(p) {
+            primes.push(p);
+            remaining /= p;
+        }
+    }
+
+    let mut stack = vec![remaining];
+    while let Some(m) = stack.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime(m) {
+            primes.push(m);
+            continue;
+        }
+        let d = pollard_rho(m);
+        stack.push(d);
+        stack.push(m / d);
+    }
+
+    primes.sort_unstable();
+    let mut factors: Vec<(u128, u32)> = vec![];
+    for p in primes {
+        match factors.last_mut() {
+            Some((q, e)) if *q == p => *e += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+
+    factors
+}
+
+// Deterministic Miller-Rabin primality test.
+// Using the first twelve primes as bases is exact for every `n` below 3.3 * 10^24.
+pub fn is_prime(n: u128) -> bool {
+    const BASES: [u128; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    for p in BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for a in BASES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+
+    true
+}
+
+// Finds a non-trivial factor of the composite odd number `n` with Pollard's rho (Floyd cycle detection).
+fn pollard_rho(n: u128) -> u128 {
+    for c in 1.. {
+        let f = |x: u128| (mul_mod(x, x, n) + c) % n;
+        let (mut x, mut y, mut d) = (2u128, 2u128, 1u128);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+    }
+
+    unreachable!("pollard rho always finds a factor of a composite number")
+}
+
+// Greatest common divisor of two unsigned integers.
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+// Computes `a * b mod m` without overflowing, falling back to double-and-add for moduli above 64 bits.
+fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    if m <= u64::MAX as u128 {
+        return (a % m) * (b % m) % m;
+    }
+
+    let (mut a, mut b, mut result) = (a % m, b % m, 0u128);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+
+    result
+}
+
+// Computes `a + b mod m` for reduced operands without overflowing.
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+// Computes `base^exponent mod m` by square-and-multiply.
+fn pow_mod(base: u128, mut exponent: u128, m: u128) -> u128 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exponent >>= 1;
+    }
+
+    result
+}
+
 // Extended Euclidean algorithm used to compute the greatest common divisor (gcd) of two integers.
 // This is used to find the multiplicative inverse in the field.
 pub fn extended_euclidean_algorithm(a: i128, b: i128) -> (i128, i128, i128) {
@@ -256,6 +428,7 @@ pub fn extended_euclidean_algorithm(a: i128, b: i128) -> (i128, i128, i128) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{batch_inverse, FriError};
 
     #[test]
     fn extended_euclidean_algorithm_works() {
@@ -318,6 +491,12 @@ mod tests {
         let c = FieldElement::new(27, field);
 
         assert_eq!(a.pow(3), c);
+
+        // large exponents must not overflow: 28 has order 32 modulo 97
+        let g = FieldElement::new(28, field);
+        assert_eq!(g.pow(32), field.one());
+        assert_eq!(g.pow(16), FieldElement::new(96, field));
+        assert_eq!(g.pow(1000), g.pow(1000 % 32));
     }
 
     #[test]
@@ -326,14 +505,107 @@ mod tests {
 
         let mut a = FieldElement::new(2, field);
         let mut b = FieldElement::new(7, field);
-        let mut c = FieldElement::new(2, field);
+        let mut c = FieldElement::new(28, field);
 
         assert_eq!(a / b, c);
 
         a = FieldElement::new(7, field);
         b = FieldElement::new(5, field);
-        c = FieldElement::new(7, field);
+        c = FieldElement::new(79, field);
 
         assert_eq!(a / b, c);
+        assert_eq!(c * b, a);
+    }
+
+    #[test]
+    fn factorize_works() {
+        assert_eq!(factorize(96), vec![(2, 5), (3, 1)]);
+        assert_eq!(factorize(1), vec![]);
+        assert_eq!(factorize(97), vec![(97, 1)]);
+        // p - 1 for the Goldilocks prime 2^64 - 2^32 + 1
+        assert_eq!(
+            factorize(18446744069414584320),
+            vec![(2, 32), (3, 1), (5, 1), (17, 1), (257, 1), (65537, 1)]
+        );
+        // product of two large primes, only reachable with Pollard's rho
+        assert_eq!(
+            factorize(1000000007 * 998244353),
+            vec![(998244353, 1), (1000000007, 1)]
+        );
+        assert!(is_prime(2305843009213693951)); // 2^61 - 1
+        assert!(!is_prime(3215031751)); // strong pseudoprime to bases 2, 3, 5, 7
+    }
+
+    #[test]
+    fn field_generator_and_two_adicity() {
+        let field = Field::new(97);
+        assert_eq!(field.one().two_adicity(), 5);
+        assert_eq!(field.generator(), FieldElement::new(5, field));
+
+        // 998244353 = 119 * 2^23 + 1 has generator 3
+        let field = Field::new(998244353);
+        assert_eq!(field.one().two_adicity(), 23);
+        assert_eq!(field.generator(), FieldElement::new(3, field));
+    }
+
+    #[test]
+    fn field_primitive_nth_root() {
+        let field = Field::new(97);
+        assert_eq!(
+            field.one().primitive_nth_root(32).unwrap(),
+            FieldElement::new(28, field)
+        );
+
+        for n in [1, 2, 3, 4, 6, 8, 12, 16, 24, 32, 48, 96] {
+            let root = field.one().primitive_nth_root(n).unwrap();
+            assert_eq!(root.pow(n), field.one());
+            for (q, _) in factorize(n) {
+                assert_ne!(root.pow(n / q), field.one());
+            }
+        }
+
+        assert_eq!(
+            field.one().primitive_nth_root(64),
+            Err(FriError::NoRootOfUnity { order: 64 })
+        );
+        assert!(field.one().primitive_nth_root(5).is_err());
+
+        let field = Field::new(998244353);
+        let root = field.one().primitive_nth_root(1 << 23).unwrap();
+        assert_eq!(root.pow(1 << 23), field.one());
+        assert_ne!(root.pow(1 << 22), field.one());
+    }
+
+    #[test]
+    fn finite_field_inverse() {
+        let field = Field::new(97);
+
+        for num in 1..97 {
+            let a = FieldElement::new(num, field);
+            assert_eq!(a * a.inverse(), field.one());
+        }
+    }
+
+    #[test]
+    fn finite_field_batch_inverse() {
+        let field = Field::new(97);
+        let values: Vec<FieldElement> = (1..97).map(|num| FieldElement::new(num, field)).collect();
+
+        let inverses = batch_inverse(&values);
+        for (a, a_inv) in values.iter().zip(inverses.iter()) {
+            assert_eq!(*a_inv, a.inverse());
+        }
+        assert!(batch_inverse::<FieldElement>(&[]).is_empty());
+    }
+
+    #[test]
+    fn finite_field_contains_reduced_elements() {
+        let field = Field::new(97);
+
+        assert!(field.contains(&FieldElement::new(0, field)));
+        assert!(field.contains(&FieldElement::new(96, field)));
+        assert!(!field.contains(&FieldElement { num: 97, field }));
+        assert!(!field.contains(&FieldElement { num: -1, field }));
+        assert!(!field.contains(&FieldElement::new(5, Field::new(193))));
     }
 }
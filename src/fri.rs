@@ -1,245 +1,723 @@
-use std::ops::Neg;
-
-use crate::{fold_polynomial, FieldElement, Polynomial, ProofStream};
-use merkle::{MerkleTree, Proof};
-use ring::{digest::Algorithm, digest::SHA256};
-
-static DIGEST: &Algorithm = &SHA256;
+use crate::{absorb_ood_evaluation, deep_quotient, open_codeword, sample_ood_point};
+use crate::{
+    batch_inverse, EvaluationDomain, FriError, FriParams, Polynomial, PrimeField, ProofStream,
+};
+use crate::{verify_quotient_openings, DeepProof, Quotient, VerificationError};
+use crate::{Hashable, MerkleTree, Ntt, Proof};
+use ring::digest::Algorithm;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug)]
-pub struct FriLayer {
-    pub polynomial: Polynomial, // The polynomial associated with this FRI layer.
-    pub merkle_tree: MerkleTree<FieldElement>, // Merkle tree for commitments based on the polynomial evaluation.
-    pub domain: Vec<FieldElement>,             // Domain over with the polynomial is evaluated.
+pub struct FriLayer<F> {
+    pub evaluations: Vec<F>, // Committed codeword, the layer's values over the domain.
+    pub merkle_tree: MerkleTree<Vec<F>>, // Merkle tree over the cosets of `folding_factor` evaluations.
+    pub domain: EvaluationDomain<F>,     // Domain over with the codeword is evaluated.
+    pub folding_factor: usize, // Number of evaluations folded together into the next layer.
 }
 
-impl FriLayer {
-    // Constructs a new `FriLayer` with a given polynomial, coset offset, and domain.
+impl<F: PrimeField> FriLayer<F> {
+    // Constructs a new `FriLayer` with a given polynomial and evaluation domain, using the folding factor
+    // and hash function of `params`.
     // The polynomial is evaluated over the domain, and a Merkle tree is created based on the evaluations.
-    pub fn new(poly: &Polynomial, domain: Vec<FieldElement>) -> Self {
-        let evaluation = poly.evaluate_domain(&domain);
+    pub fn new(poly: &Polynomial<F>, domain: EvaluationDomain<F>, params: &FriParams) -> Self {
+        let evaluations = poly.evaluate_coset(&domain);
+
+        Self::from_codeword(domain, evaluations, params)
+    }
 
-        let merkle_tree = MerkleTree::from_vec(DIGEST, evaluation.clone());
+    // Constructs a new `FriLayer` from an already computed codeword, e.g. the output of `low_degree_extend`
+    // or the fold of the previous layer. The sizes are checked by the prover entry points against the
+    // validated `params`, whose folding factor divides the domain size, so they are only debug asserts here.
+    // The evaluations are grouped by the points that fold together: leaf `j` holds the evaluations
+    // at indices `j + t * size / folding_factor`, i.e. at `x * z^t` for a primitive
+    // `folding_factor`-th root of unity `z`, which all map to `x^folding_factor` in the next layer.
+    pub fn from_codeword(
+        domain: EvaluationDomain<F>,
+        evaluations: Vec<F>,
+        params: &FriParams,
+    ) -> Self {
+        let folding_factor = params.folding_factor();
+        debug_assert_eq!(
+            evaluations.len(),
+            domain.size,
+            "codeword and domain sizes differ"
+        );
+        debug_assert!(
+            folding_factor >= 2 && domain.size.is_multiple_of(folding_factor),
+            "folding factor {} does not divide the domain size {}",
+            folding_factor,
+            domain.size
+        );
+
+        let leaf_count = domain.size / folding_factor;
+        let leaves = (0..leaf_count)
+            .map(|j| {
+                (0..folding_factor)
+                    .map(|t| evaluations[j + t * leaf_count])
+                    .collect()
+            })
+            .collect();
+        let merkle_tree = MerkleTree::from_vec(params.hash().algorithm(), leaves);
 
         Self {
-            polynomial: poly.clone(),
+            evaluations,
             merkle_tree,
             domain,
+            folding_factor,
         }
     }
 }
 
+/// Folds a codeword over `domain` into the codeword of the next layer, over `domain.fold(folding_factor)`,
+/// without going through coefficient form.
+///
+/// Each binary fold pairs `x = domain.element(j)` with `-x = domain.element(j + size / 2)` and computes
+/// `f'(x^2) = (f(x) + f(-x)) / 2 + alpha * (f(x) - f(-x)) / (2x)`, with all the `1 / 2x` obtained by a
+/// single batch inversion. Folding by `2^m` is `m` binary folds with the challenges `alpha, alpha^2,
+/// alpha^4, ...`, which matches `fold_polynomial` on the underlying coefficients.
+pub fn fold_codeword<F: PrimeField>(
+    codeword: &[F],
+    domain: &EvaluationDomain<F>,
+    alpha: &F,
+    folding_factor: usize,
+) -> Vec<F> {
+    assert!(
+        folding_factor >= 2 && folding_factor.is_power_of_two(),
+        "folding factor must be a power of two of at least 2"
+    );
+    assert_eq!(
+        codeword.len(),
+        domain.size,
+        "codeword and domain sizes differ"
+    );
+    let one = domain.generator.one();
+    let two_inverse = (one + one).inverse();
+
+    let mut codeword = codeword.to_vec();
+    let mut domain = *domain;
+    let mut alpha = *alpha;
+    for _ in 0..folding_factor.trailing_zeros() {
+        let half = domain.size / 2;
+        let points: Vec<F> = domain.iter().take(half).collect();
+        let point_inverses = batch_inverse(&points);
+
+        codeword = (0..half)
+            .map(|j| {
+                let (value, value_sym) = (codeword[j], codeword[j + half]);
+                let even = (value + value_sym) * two_inverse;
+                let odd = (value - value_sym) * two_inverse * point_inverses[j];
+                even + alpha * odd
+            })
+            .collect();
+        domain = domain.square();
+        alpha = alpha * alpha;
+    }
+
+    codeword
+}
+
+// Transcript labels, so that every message and challenge of the protocol is domain separated.
+const FINAL_POLYNOMIAL_LABEL: &str = "fri_final_polynomial";
+const GRINDING_LABEL: &str = "fri_grinding_nonce";
+const QUERY_LABEL: &str = "fri_query_indices";
+
+// Label of the Merkle root committing to layer `i`.
+fn root_label(i: usize) -> String {
+    format!("fri_layer_root_{}", i)
+}
+
+// Label of the challenge used to fold layer `i` into layer `i + 1`.
+fn alpha_label(i: usize) -> String {
+    format!("fri_fold_alpha_{}", i)
+}
+
+// Encodes the coefficients of the final polynomial for the transcript.
+fn final_polynomial_bytes<F: PrimeField>(poly: &Polynomial<F>) -> Vec<u8> {
+    poly.coeffs
+        .iter()
+        .flat_map(|coeff| coeff.to_bytes())
+        .collect()
+}
+
 // The commit phase of the FRI protocol.
-// This phase is responsible for generating commitments to multiple layers of polynomials and storing them in a proof transcript.
-pub fn fri_commit(
-    number_layers: usize,         // The number of layers in the FRI commitment.
-    p_0: Polynomial,              // Initial polynomial.
+// This phase is responsible for generating commitments to multiple layers of codewords and storing them in a proof transcript.
+// The prover only needs the committed codeword: each round folds `folding_factor` cosets together
+// directly on the evaluations, dividing the degree and the domain size by that factor.
+// Folding stops after `params.number_layers()` commitments, once the degree bound is at most
+// `final_poly_max_degree + 1`, and the remaining polynomial is sent in the clear.
+pub fn fri_commit<F: PrimeField>(
+    codeword: Vec<F>, // Initial codeword, the values committed over `domain`.
     transcript: &mut ProofStream, // Proof stream to store commitments.
-    domain: &Vec<FieldElement>,   // Domain of the FRI layers.
-) -> (FieldElement, Vec<FriLayer>) {
-    let field = p_0.coeffs[0].field;
+    domain: &EvaluationDomain<F>, // Domain of the first layer.
+    params: &FriParams, // Public parameters, giving the number of layers and the folding factor.
+) -> (Polynomial<F>, Vec<FriLayer<F>>) {
+    let number_layers = params.number_layers();
+    let folding_factor = params.folding_factor();
+    let field = domain.generator;
 
     // setup phase
     let mut fri_layers = Vec::with_capacity(number_layers);
-    let mut current_layer = FriLayer::new(&p_0, domain.clone());
+    let mut current_layer = FriLayer::from_codeword(*domain, codeword, params);
     fri_layers.push(current_layer.clone());
-    let mut current_poly = p_0;
 
     // send first commitment
-    transcript.push(current_layer.merkle_tree.root_hash());
+    transcript.absorb(&root_label(0), current_layer.merkle_tree.root_hash());
 
     // begin the interactive phase
     for i in 1..number_layers {
-        // recieve challange
-        let alpha = transcript.prover_fiat_shamir(&field);
-
-        // Compute layer polynomial and domain
-        let new_domain = domain[i];
-        println!("folding with: {:?}", &alpha);
-        current_poly = fold_polynomial(&current_poly, &alpha);
-        current_layer = FriLayer::new(&current_poly, vec![new_domain]);
+        // recieve challange for folding the previous layer
+        let alpha = transcript.squeeze_field_element(&alpha_label(i - 1), &field);
+
+        // Compute layer codeword and domain
+        let new_domain = current_layer.domain.fold(folding_factor);
+        let new_codeword = fold_codeword(
+            &current_layer.evaluations,
+            &current_layer.domain,
+            &alpha,
+            folding_factor,
+        );
+        current_layer = FriLayer::from_codeword(new_domain, new_codeword, params);
         let new_data = current_layer.merkle_tree.root_hash();
         fri_layers.push(current_layer.clone());
 
         // sending commitment
-        transcript.push(new_data);
+        transcript.absorb(&root_label(i), new_data);
     }
 
     // last round
     // receive challange
-    let alpha = transcript.prover_fiat_shamir(&field);
+    let alpha = transcript.squeeze_field_element(&alpha_label(fri_layers.len() - 1), &field);
+
+    // an honest last fold has degree below the final degree bound, so its interpolant is the final polynomial;
+    // for any other codeword the higher coefficients are dropped and the queries expose the mismatch
+    let final_domain = current_layer.domain.fold(folding_factor);
+    let final_codeword = fold_codeword(
+        &current_layer.evaluations,
+        &current_layer.domain,
+        &alpha,
+        folding_factor,
+    );
+    let mut final_polynomial = Polynomial::interpolate(&final_domain, &final_codeword);
+    final_polynomial
+        .coeffs
+        .truncate(params.final_degree_bound());
+    final_polynomial.normalize();
 
-    let last_poly = fold_polynomial(&current_poly, &alpha);
+    // send the final polynomial's coefficients
+    transcript.absorb(
+        FINAL_POLYNOMIAL_LABEL,
+        &final_polynomial_bytes(&final_polynomial),
+    );
 
-    let zero = FieldElement::new(0, field);
-    let last_value = last_poly.coeffs.first().unwrap_or(&zero);
+    (final_polynomial, fri_layers)
+}
 
-    // send last value as raw byte
-    transcript.push(&last_value.num.to_be_bytes().to_vec());
+// The proof-of-work step between the commit and query phases.
+// The prover searches for a nonce giving `grinding_bits` leading zeros and absorbs it, which makes
+// every attempt to re-roll the query positions cost `2^grinding_bits` hashes.
+pub fn fri_grind(transcript: &mut ProofStream, params: &FriParams) -> u64 {
+    let nonce = transcript.grind(GRINDING_LABEL, params.grinding_bits());
+    transcript.absorb(GRINDING_LABEL, &nonce.to_be_bytes());
 
-    (*last_value, fri_layers)
+    nonce
 }
 
-/// The `FriDecommitment` struct holds evaluation pairs and authentication paths
-/// for verifying FRI decommitments.
-#[derive(Debug, Clone)]
-pub struct FriDecommitment {
-    pub layers_auth_paths_sym: Vec<Option<Proof<FieldElement>>>,
-    pub layers_evaluations_sym: Vec<FieldElement>,
-    pub layers_auth_paths: Vec<Option<Proof<FieldElement>>>,
-    pub layers_evaluations: Vec<FieldElement>,
+/// The `FriDecommitment` struct holds, for every layer, the opened coset of
+/// `folding_factor` evaluations and its authentication path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FriDecommitment<F> {
+    pub layers_auth_paths: Vec<Option<Proof<Vec<F>>>>,
+    pub layers_evaluations: Vec<Vec<F>>,
 }
 
 // The query phase of the FRI protocol.
-// Verifies whether the values at randomly selected points in the domain match the polynomial evaluations.
-pub fn fri_query_phase(
-    g: FieldElement,              // nth root of unity for domain evaluation.
-    domain_size: usize,           // size of the domain.
-    fri_layers: &Vec<FriLayer>,   // FRI layers generated during the commit phase.
+// Opens the committed codewords at randomly selected points of the first domain. A query at index `idx`
+// opens the leaf holding `x = g^idx` and its siblings `x * z^t` in the first layer,
+// then follows `x^folding_factor` down through every subsequent layer.
+pub fn fri_query_phase<F: PrimeField>(
+    fri_layers: &[FriLayer<F>], // FRI layers generated during the commit phase.
     transcript: &mut ProofStream, // Proof stream for handling challanges.
-    number_of_queries: usize,     // Number of queries to be made in the protocol.
-) -> Vec<FriDecommitment> {
-    if !fri_layers.is_empty() {
-        let mut decommitments = Vec::with_capacity(number_of_queries);
-
-        // Generate a list of random indices
-        let query_indices = (0..number_of_queries as i32)
-            .map(|_| transcript.verifier_random_index(domain_size))
-            .collect::<Vec<usize>>();
-
-        // Process each query index
-        for &query_index in query_indices.iter() {
-            let mut layers_auth_paths_sym = vec![];
-            let mut layers_evaluations_sym = vec![];
+    params: &FriParams,         // Public parameters, giving the number of queries.
+) -> Vec<FriDecommitment<F>> {
+    match fri_layers.first() {
+        Some(layer) => {
+            let query_indices = fri_query_indices(transcript, layer.domain.size, params);
+            fri_decommit(fri_layers, &query_indices)
+        }
+        None => vec![],
+    }
+}
+
+// Derives the query indices into a first domain of size `domain_size` from the transcript,
+// after all commitments were sent.
+pub fn fri_query_indices(
+    transcript: &mut ProofStream,
+    domain_size: usize,
+    params: &FriParams,
+) -> Vec<usize> {
+    transcript.squeeze_indices(QUERY_LABEL, params.number_of_queries(), domain_size)
+}
+
+// Opens every layer at the cosets followed by the queries at `query_indices`.
+pub fn fri_decommit<F: PrimeField>(
+    fri_layers: &[FriLayer<F>], // FRI layers generated during the commit phase.
+    query_indices: &[usize],    // Query indices into the first domain.
+) -> Vec<FriDecommitment<F>> {
+    query_indices
+        .iter()
+        .map(|&query_index| {
             let mut layers_evaluations = vec![];
             let mut layers_auth_paths = vec![];
 
             // Iterate over each layer in the FRI layers
-            for (i, layer) in fri_layers.iter().enumerate() {
-                // Get the power of g for the current layer
-                let g_i = g.pow(i as u32 + 1);
-                let neg_g_i = g_i.neg(); // Compute -g^i
-
-                // Evaluate the polynomial at g^i and -g^i
-                let eval = layer.polynomial.evaluate(g_i);
-                let eval_sym = layer.polynomial.evaluate(neg_g_i);
+            for layer in fri_layers.iter() {
+                // Leaf holding the query point of the current layer; each layer divides the domain.
+                let leaf_count = layer.domain.size / layer.folding_factor;
+                let leaf_index = query_index % leaf_count;
 
-                // Generate Merkle proofs for the evaluations at g^i and -g^i
-                let auth_path = layer.merkle_tree.gen_nth_proof(query_index);
-                let auth_path_sym = layer
-                    .merkle_tree
-                    .gen_nth_proof((query_index + domain_size / 2) % domain_size); // Symmetric point proof
+                // Generate the Merkle proof for the coset of committed values
+                let auth_path = layer.merkle_tree.gen_nth_proof(leaf_index);
+                let evaluations = (0..layer.folding_factor)
+                    .map(|t| layer.evaluations[leaf_index + t * leaf_count])
+                    .collect();
 
                 // Push results into the vectors
-                layers_evaluations.push(eval);
-                layers_evaluations_sym.push(eval_sym);
+                layers_evaluations.push(evaluations);
                 layers_auth_paths.push(auth_path);
-                layers_auth_paths_sym.push(auth_path_sym);
             }
 
-            // Store the decommitment for this query
-            decommitments.push(FriDecommitment {
-                layers_auth_paths_sym,
-                layers_evaluations_sym,
+            FriDecommitment {
                 layers_evaluations,
                 layers_auth_paths,
-            });
+            }
+        })
+        .collect()
+}
+
+/// The `FriProof` struct bundles everything the prover sends to the verifier:
+/// the Merkle root of each committed layer, the final polynomial, the proof-of-work
+/// nonce and the decommitments for every query, plus the DEEP step when the parameters enable it.
+/// It holds no prover-side `FriLayer` data, so it can be serialized, shipped and checked on its own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FriProof<F> {
+    pub layer_roots: Vec<Vec<u8>>, // Merkle root of each FRI layer, in commit order.
+    pub final_polynomial: Polynomial<F>, // Low-degree polynomial the last layer folds down to.
+    pub pow_nonce: u64,            // Proof-of-work nonce found by `fri_grind`.
+    pub decommitments: Vec<FriDecommitment<F>>, // One decommitment per query.
+    pub deep: Option<DeepProof<F>>, // Commitment and openings of the original codeword, with DEEP sampling.
+}
+
+impl<F: PrimeField> FriProof<F> {
+    // Assembles a proof from the output of `fri_commit`, `fri_grind` and `fri_query_phase`, without DEEP step.
+    pub fn new(
+        fri_layers: &[FriLayer<F>],
+        final_polynomial: Polynomial<F>,
+        pow_nonce: u64,
+        decommitments: Vec<FriDecommitment<F>>,
+    ) -> Self {
+        let layer_roots = fri_layers
+            .iter()
+            .map(|layer| layer.merkle_tree.root_hash().clone())
+            .collect();
+
+        Self {
+            layer_roots,
+            final_polynomial,
+            pow_nonce,
+            decommitments,
+            deep: None,
         }
+    }
 
-        decommitments
-    } else {
-        vec![]
+    // Returns, for every query, the opened coset of the codeword the prover committed to: the first layer,
+    // or with DEEP sampling the original codeword the first layer is the quotient of. Protocols running FRI
+    // on a codeword derived from their own commitments check it against these, once FRI verified them.
+    pub fn committed_cosets(&self) -> Vec<&[F]> {
+        match &self.deep {
+            Some(deep) => deep
+                .openings
+                .iter()
+                .map(|opening| opening.evaluations.as_slice())
+                .collect(),
+            None => first_layer_cosets(&self.decommitments),
+        }
+    }
+
+    // Serializes the proof with `proof_to_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        proof_to_bytes(self)
+    }
+
+    // Reconstructs the proof from the bytes produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        proof_from_bytes(bytes)
     }
 }
 
-// Verifies the results of the FRI query phase.
-// This function checks the validity of the decommitment by verifying the Merkle proofs
-// and confirming the polynomial folding consistency across the FRI layers.
-pub fn verify_fri(
-    fri_layers: &Vec<FriLayer>, // FRI layers generated during the commit phase.
-    decommitments: &Vec<FriDecommitment>, // Decommitments provided during the query phase.
-    transcript: &mut ProofStream, // Proof stream for handling challenges.
-    g: FieldElement,            // the nth root of unity that the function is evaluated at
-) -> bool {
+// Serializes any proof of this crate to bytes so it can be sent to a verifier.
+pub fn proof_to_bytes<T: Serialize>(proof: &T) -> Vec<u8> {
+    serde_json::to_vec(proof).expect("Serialization failed")
+}
+
+// Reconstructs a proof from the bytes produced by `proof_to_bytes`.
+pub fn proof_from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, serde_json::Error> {
+    serde_json::from_slice(bytes)
+}
+
+// Returns the coset opened in the first layer by every decommitment.
+fn first_layer_cosets<F>(decommitments: &[FriDecommitment<F>]) -> Vec<&[F]> {
+    decommitments
+        .iter()
+        .map(|decommitment| decommitment.layers_evaluations[0].as_slice())
+        .collect()
+}
+
+// Verifies a FRI proof.
+// This function checks the validity of the decommitments by verifying the Merkle proofs against
+// the roots carried in the proof and confirming the polynomial folding consistency across the layers.
+// Only the proof and the public parameters are used, never the prover's `FriLayer`s.
+pub fn verify_fri<F: PrimeField>(
+    proof: &FriProof<F>,          // Proof produced by the commit and query phases.
+    domain: &EvaluationDomain<F>, // Domain of the first layer.
+    params: &FriParams,           // Public parameters the prover committed with.
+) -> Result<(), VerificationError> {
+    verify_fri_with_transcript(proof, domain, params, &mut ProofStream::new()).map(|_| ())
+}
+
+// Verifies a FRI proof whose commit phase continued `transcript`, for protocols that absorb their own
+// messages before running FRI, such as batching. The transcript must be in the state the prover's was
+// when `fri_commit` started. On success, returns the query indices into the first domain so that the
+// caller can check its own openings at the same positions.
+pub fn verify_fri_with_transcript<F: PrimeField>(
+    proof: &FriProof<F>,          // Proof produced by the commit and query phases.
+    domain: &EvaluationDomain<F>, // Domain of the first layer.
+    params: &FriParams,           // Public parameters the prover committed with.
+    transcript: &mut ProofStream, // Transcript replaying the prover's.
+) -> Result<Vec<usize>, VerificationError> {
+    let number_layers = params.number_layers();
+    let folding_factor = params.folding_factor();
+    let algorithm = params.hash().algorithm();
+    let field = domain.generator;
+    if domain.size != params.domain_size() {
+        return Err(FriError::DomainSizeMismatch {
+            expected: params.domain_size(),
+            found: domain.size,
+        }
+        .into());
+    }
+    if proof.layer_roots.len() != number_layers {
+        return Err(VerificationError::LayerCountMismatch {
+            expected: number_layers,
+            found: proof.layer_roots.len(),
+        });
+    }
+
+    // With DEEP sampling, replay the commitment to the original codeword and its out-of-domain evaluation,
+    // which fix the quotient FRI runs on.
+    let quotient = match (&proof.deep, params.deep_sampling()) {
+        (Some(deep), true) => {
+            check_reduced([&deep.ood_evaluation], &field)?;
+            let z = sample_ood_point(transcript, &deep.root, domain);
+            let gamma = absorb_ood_evaluation(transcript, &deep.ood_evaluation, &field);
+            Some(Quotient {
+                points: vec![z],
+                values: vec![deep.ood_evaluation],
+                gamma,
+                degree_bound: params.degree_bound(),
+            })
+        }
+        (None, false) => None,
+        _ => return Err(VerificationError::DeepStepMismatch),
+    };
+
+    // Replay the commit phase on the transcript to recover the folding challenges,
+    // absorbing the roots from the proof in the same order and with the same labels as the prover.
+    let roots = &proof.layer_roots;
+    let mut alphas = Vec::with_capacity(number_layers);
+    for (i, root) in roots.iter().enumerate() {
+        transcript.absorb(&root_label(i), root);
+        alphas.push(transcript.squeeze_field_element(&alpha_label(i), &field));
+    }
+
+    // Parse the claimed final polynomial before it enters the transcript: its encoding must be the
+    // one the honest prover sends, otherwise the same polynomial could be re-encoded to re-roll the queries.
+    if !is_canonical_final_polynomial(&proof.final_polynomial, &field, params) {
+        return Err(VerificationError::NonCanonicalFinalPolynomial {
+            max_coefficients: params.final_degree_bound(),
+        });
+    }
+    transcript.absorb(
+        FINAL_POLYNOMIAL_LABEL,
+        &final_polynomial_bytes(&proof.final_polynomial),
+    );
+
+    // Check the proof-of-work before it is absorbed, as the prover did.
+    if !transcript.check_grinding(GRINDING_LABEL, proof.pow_nonce, params.grinding_bits()) {
+        return Err(VerificationError::ProofOfWork);
+    }
+    transcript.absorb(GRINDING_LABEL, &proof.pow_nonce.to_be_bytes());
+
+    // Re-derive the query indices exactly as the prover did.
+    let query_indices = fri_query_indices(transcript, domain.size, params);
+    if proof.decommitments.len() != query_indices.len() {
+        return Err(VerificationError::QueryCountMismatch {
+            expected: query_indices.len(),
+            found: proof.decommitments.len(),
+        });
+    }
+
     // Iterate over each decommitment and verify it
-    for (query_index, decommitment) in decommitments.iter().enumerate() {
+    let ntt = coset_ntt(domain, folding_factor);
+    for (query_index, (decommitment, &index)) in proof
+        .decommitments
+        .iter()
+        .zip(query_indices.iter())
+        .enumerate()
+    {
+        // A well-formed decommitment opens one coset of every layer.
+        if decommitment.layers_evaluations.len() != number_layers
+            || decommitment.layers_auth_paths.len() != number_layers
+            || decommitment
+                .layers_evaluations
+                .iter()
+                .any(|evaluations| evaluations.len() != folding_factor)
+        {
+            return Err(VerificationError::MalformedDecommitment { query_index });
+        }
+        check_reduced(decommitment.layers_evaluations.iter().flatten(), &field)?;
+
+        // The value the previous layer folds to at the query point, if any.
+        let mut folded_value: Option<F> = None;
+        let mut layer_domain = *domain;
+
         // for each layer, we need to verify the Merkle proof and consistency with the evaluations
-        for (i, layer) in fri_layers.iter().enumerate() {
-            // Extract the evaluation and the Merkle authentication path for both g and -g.
-            let eval = decommitment.layers_evaluations[i];
-            let eval_sym = decommitment.layers_evaluations_sym[i];
+        for (i, root) in roots.iter().enumerate() {
+            let evaluations = &decommitment.layers_evaluations[i];
             let auth_path = &decommitment.layers_auth_paths[i];
-            let auth_path_sym = &decommitment.layers_auth_paths_sym[i];
 
-            // Verify the Merkle proof for evaluation at g^i
-            let eval_proof_valid = match auth_path {
-                Some(proof) => proof.validate(layer.merkle_tree.root_hash()),
-                None => false,
-            };
-
-            // Verify the Merkle proof for evaluation at g^-1
-            let eval_sym_proof_valid = match auth_path_sym {
-                Some(proof) => proof.validate(layer.merkle_tree.root_hash()),
-                None => false,
-            };
+            // The layer must be opened at the leaf holding the query point.
+            let leaf_count = layer_domain.size / folding_factor;
+            let position = index % layer_domain.size;
+            let leaf_index = position % leaf_count;
 
-            // Both Merkle proofs must be valid.
-            if !eval_proof_valid || !eval_sym_proof_valid {
-                println!(
-                    "Merkle proof verification failed at layer {}, at query index {}",
-                    i, query_index
-                );
-                return false;
-            } else {
-                println!(
-                    "Merkle proof verification passed at layer {}, at query index {}",
-                    i, query_index
-                );
+            // Verify the Merkle proof for the opened coset
+            if !verify_auth_path(
+                auth_path,
+                algorithm,
+                root,
+                evaluations,
+                leaf_index,
+                leaf_count,
+            ) {
+                return Err(VerificationError::LayerAuthentication {
+                    layer: i,
+                    query_index,
+                });
             }
 
-            // Check consistency with the next layer by verifying that folding was done correctly.
-            // This can be done by recomputing the folded polynomial from eval and eval_sym and comparing.
-            // TODO: this does not produce the right result
-            if i < fri_layers.len() - 1 {
-                let alpha = transcript.verifier_fiat_shamir(&eval.field);
-                let folded_value = folded_polynomial_evaluation(eval, eval_sym, &alpha, g);
-
-                // The folded value must match the next layer's evaluation at g^(i+1).
-                let next_eval = decommitment.layers_evaluations[i + 1];
-                if folded_value != next_eval {
-                    println!("Folding consistency check failed at layer {}", i);
-                    return false;
-                } else {
-                    println!("Folding consistency check passed at layer {}", i);
+            // Check consistency with the previous layer by verifying that folding was done correctly:
+            // the value folded from the previous coset must be the committed value at the query point.
+            if let Some(expected) = folded_value {
+                if evaluations[position / leaf_count] != expected {
+                    return Err(VerificationError::FoldingMismatch {
+                        layer: i - 1,
+                        query_index,
+                    });
                 }
             }
+
+            // Fold the opened coset `x * <z>` with the layer's challenge.
+            let coset = EvaluationDomain {
+                size: folding_factor,
+                generator: layer_domain.generator.pow(leaf_count as u128),
+                offset: layer_domain.element(leaf_index),
+            };
+            folded_value = Some(folded_coset_evaluation(
+                &coset,
+                evaluations,
+                &alphas[i],
+                &ntt,
+            ));
+            layer_domain = layer_domain.fold(folding_factor);
+        }
+
+        // The last layer folds down to the final polynomial sent by the prover,
+        // evaluated at the query point of the final domain.
+        let final_point = layer_domain.element(index % layer_domain.size);
+        if folded_value != Some(proof.final_polynomial.evaluate(final_point)) {
+            return Err(VerificationError::FinalPolynomialMismatch { query_index });
+        }
+    }
+
+    // The first layer must be the DEEP quotient of the original codeword at the query positions.
+    if let (Some(deep), Some(quotient)) = (&proof.deep, &quotient) {
+        verify_quotient_openings(
+            &deep.root,
+            &deep.openings,
+            &first_layer_cosets(&proof.decommitments),
+            &query_indices,
+            quotient,
+            domain,
+            params,
+        )?;
+    }
+
+    // If all checks pass, return the positions that were checked
+    Ok(query_indices)
+}
+
+// Proves that `codeword` is close to the evaluations over `domain` of a polynomial of degree below
+// `params.degree_bound()`.
+// The prover does not need to know such a polynomial: the codeword is committed as is and folded
+// until the degree bound reaches one, so a codeword far from the Reed-Solomon code fails
+// the verifier's checks with high probability.
+pub fn prove_low_degree<F: PrimeField>(
+    codeword: Vec<F>,             // Committed values, one per domain element.
+    domain: &EvaluationDomain<F>, // Domain of the codeword.
+    params: &FriParams,           // Public parameters, including the claimed degree bound.
+) -> Result<FriProof<F>, FriError> {
+    if domain.size != params.domain_size() {
+        return Err(FriError::DomainSizeMismatch {
+            expected: params.domain_size(),
+            found: domain.size,
+        });
+    }
+    if codeword.len() != domain.size {
+        return Err(FriError::CodewordLengthMismatch {
+            expected: domain.size,
+            found: codeword.len(),
+        });
+    }
+
+    Ok(prove_fri_with_transcript(
+        codeword,
+        domain,
+        params,
+        &mut ProofStream::new(),
+    ))
+}
+
+// Runs the whole FRI prover on `codeword` continuing `transcript`, for protocols that absorb their own
+// messages first, such as batching; `verify_fri_with_transcript` is its counterpart.
+// With DEEP sampling, the codeword is committed as is and FRI runs on its DEEP quotient, whose first
+// layer is tied back to the codeword by opening it at every query.
+// The codeword must have one value per element of a domain of size `params.domain_size()`.
+pub fn prove_fri_with_transcript<F: PrimeField>(
+    codeword: Vec<F>,             // Committed values, one per domain element.
+    domain: &EvaluationDomain<F>, // Domain of the codeword.
+    params: &FriParams,           // Public parameters, including the claimed degree bound.
+    transcript: &mut ProofStream, // Transcript of the protocol so far.
+) -> FriProof<F> {
+    let (codeword, deep_layer) = if params.deep_sampling() {
+        let layer = FriLayer::from_codeword(*domain, codeword, params);
+        let (ood_evaluation, quotient) = deep_quotient(&layer, transcript, params);
+        (quotient, Some((layer, ood_evaluation)))
+    } else {
+        (codeword, None)
+    };
+
+    let (final_polynomial, fri_layers) = fri_commit(codeword, transcript, domain, params);
+    let pow_nonce = fri_grind(transcript, params);
+    let decommitments = fri_query_phase(&fri_layers, transcript, params);
+
+    let deep = deep_layer.map(|(layer, ood_evaluation)| DeepProof {
+        root: layer.merkle_tree.root_hash().clone(),
+        ood_evaluation,
+        openings: open_codeword(&layer, &decommitments),
+    });
+
+    FriProof {
+        deep,
+        ..FriProof::new(&fri_layers, final_polynomial, pow_nonce, decommitments)
+    }
+}
+
+// Checks that the final polynomial has at most `final_degree_bound` coefficients, no trailing zero coefficient,
+// and only reduced coefficients of the field of `field`, which is how `fri_commit` encodes it.
+fn is_canonical_final_polynomial<F: PrimeField>(
+    poly: &Polynomial<F>,
+    field: &F,
+    params: &FriParams,
+) -> bool {
+    poly.coeffs.len() <= params.final_degree_bound()
+        && poly.coeffs.last().is_none_or(|coeff| !coeff.is_zero())
+        && poly.coeffs.iter().all(|coeff| coeff.is_reduced_in(field))
+}
+
+// Checks that `auth_path` opens the leaf `value` at position `index` of the tree with root `root`
+// and `count` leaves.
+pub fn verify_auth_path<L: Hashable + PartialEq>(
+    auth_path: &Option<Proof<L>>,
+    algorithm: &'static Algorithm,
+    root: &[u8],
+    value: &L,
+    index: usize,
+    count: usize,
+) -> bool {
+    match auth_path {
+        Some(proof) => {
+            proof.index == index && proof.value == *value && proof.validate(algorithm, root, count)
         }
+        None => false,
     }
+}
 
-    // If all checks pass, return true
-    true
+// Checks that every value parsed from a proof is a reduced element of the field of `field`.
+// The Merkle proofs only bind the encoding of the opened values, which a non-reduced value shares.
+pub fn check_reduced<'a, F: PrimeField + 'a>(
+    values: impl IntoIterator<Item = &'a F>,
+    field: &F,
+) -> Result<(), VerificationError> {
+    if values.into_iter().all(|value| value.is_reduced_in(field)) {
+        Ok(())
+    } else {
+        Err(VerificationError::NonReducedElement)
+    }
 }
 
-// Helper function to compute the folded polynomial evaluation.
-fn folded_polynomial_evaluation(
-    eval: FieldElement,
-    eval_sym: FieldElement,
-    alpha: &FieldElement,
-    g: FieldElement, // the nth root of unity that the function is evaluated at
-) -> FieldElement {
-    // Fold using the formula: f'(x) = (f(x) + f(-x)) / 2 + alpha * (f(x) - f(-x)) / 2x
-    let two = FieldElement::new(2, eval.field);
-    let term1 = (eval + eval_sym) / two;
-    let term2 = (eval - eval_sym) / (two * g);
-    let term2 = term2 * *alpha;
-
-    term1 + term2
+// Computes the folded polynomial evaluation from a coset of evaluations.
+// Writing f(x) = sum_t x^t f_t(x^k), the folded polynomial is f'(y) = sum_t alpha^t f_t(y).
+// The polynomial of degree below k interpolating the coset `x * <z>` is sum_t f_t(x^k) X^t,
+// so evaluating it at alpha gives f'(x^k).
+pub fn folded_coset_evaluation<F: PrimeField>(
+    coset: &EvaluationDomain<F>, // The opened points `x * z^t`.
+    values: &[F],                // The evaluations at those points.
+    alpha: &F,                   // The folding challenge of the layer.
+    ntt: &Ntt<F>,                // Transform of the subgroup `<z>`, shared by all the cosets.
+) -> F {
+    Polynomial::interpolate_with(ntt, coset, values).evaluate(*alpha)
+}
+
+// Builds the transform of the subgroup of `folding_factor`-th roots of unity in the field of `domain`.
+// Every coset opened in any layer of FRI over `domain` is a coset of that subgroup.
+pub fn coset_ntt<F: PrimeField>(domain: &EvaluationDomain<F>, folding_factor: usize) -> Ntt<F> {
+    let generator = domain.generator.pow((domain.size / folding_factor) as u128);
+
+    Ntt::new(&EvaluationDomain {
+        size: folding_factor,
+        generator,
+        offset: generator.one(),
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Field, FieldElement};
+    use crate::{low_degree_extend, Field, FieldElement, HashFunction};
+
+    fn sha256_params(degree_bound: usize, blowup: usize, folding_factor: usize) -> FriParams {
+        FriParams::new(
+            degree_bound,
+            blowup,
+            folding_factor,
+            4,
+            0,
+            0,
+            HashFunction::Sha256,
+        )
+        .unwrap()
+    }
 
     #[test]
     fn can_create_fri_layer() {
@@ -250,14 +728,472 @@ mod tests {
         let c = FieldElement::new(3, field);
         let poly = Polynomial::new(vec![a, b, c]);
 
-        let domain = vec![
-            FieldElement::new(0, field),
-            FieldElement::new(1, field),
-            FieldElement::new(2, field),
-        ];
+        // 22 has order 4 modulo 97
+        let domain = EvaluationDomain::subgroup(4, FieldElement::new(22, field)).unwrap();
+
+        let layer = FriLayer::new(&poly, domain, &sha256_params(2, 2, 2));
+
+        assert_eq!(layer.evaluations.len(), 4);
+        assert_eq!(layer.merkle_tree.count(), 2);
+    }
+
+    #[test]
+    fn fri_layer_commits_to_low_degree_extension() {
+        let (_, poly, domain, codeword) = low_degree_codeword();
+        let params = sha256_params(16, 2, 4);
+
+        let layer = FriLayer::from_codeword(domain, codeword.clone(), &params);
+
+        assert_eq!(layer.evaluations, codeword);
+        assert_eq!(
+            layer.merkle_tree.root_hash(),
+            FriLayer::new(&poly, domain, &params)
+                .merkle_tree
+                .root_hash()
+        );
+    }
+
+    #[test]
+    fn fri_layer_commits_with_the_chosen_hash() {
+        let (_, _, domain, codeword) = low_degree_codeword();
+
+        for hash in [
+            HashFunction::Sha256,
+            HashFunction::Sha384,
+            HashFunction::Sha512,
+        ] {
+            let params = FriParams::new(8, 4, 2, 4, 0, 0, hash).unwrap();
+            let layer = FriLayer::from_codeword(domain, codeword.clone(), &params);
+            assert_eq!(layer.merkle_tree.root_hash().len() * 8, hash.output_bits());
+        }
+    }
+
+    // The polynomial of coefficients 1, ..., 8 over `Field::new(97)` and its codeword with a blowup of 4,
+    // over the subgroup of order 32.
+    fn low_degree_codeword() -> (
+        Field,
+        Polynomial<FieldElement>,
+        EvaluationDomain<FieldElement>,
+        Vec<FieldElement>,
+    ) {
+        let field = Field::new(97);
+        let poly = Polynomial::new((1..9).map(|c| FieldElement::new(c, field)).collect());
+        let (domain, codeword) = low_degree_extend(&poly, 4, field.generator()).unwrap();
+
+        (field, poly, domain, codeword)
+    }
+
+    // A codeword over the subgroup of order 32 of `Field::new(97)` far from every polynomial of degree below 16.
+    fn far_codeword() -> (EvaluationDomain<FieldElement>, Vec<FieldElement>) {
+        let field = Field::new(97);
+        let domain = EvaluationDomain::from_size(32, field.generator()).unwrap();
+        let codeword = (0..32)
+            .map(|i| FieldElement::new((i * i * 31 + 7 * i + 3) % 97, field))
+            .collect();
+
+        (domain, codeword)
+    }
+
+    fn sample_proof() -> (
+        FriProof<FieldElement>,
+        EvaluationDomain<FieldElement>,
+        FriParams,
+    ) {
+        sample_proof_with_params(sha256_params(8, 4, 2))
+    }
+
+    fn sample_proof_with_params(
+        params: FriParams,
+    ) -> (
+        FriProof<FieldElement>,
+        EvaluationDomain<FieldElement>,
+        FriParams,
+    ) {
+        let field = Field::new(97);
+        let coeffs = [19, 56, 34, 48, 43, 37, 10, 0]
+            .iter()
+            .map(|c| FieldElement::new(*c, field))
+            .collect();
+        let poly = Polynomial::new(coeffs);
+
+        // 28 has order 32 modulo 97
+        let g = FieldElement::new(28, field);
+        let domain = EvaluationDomain::subgroup(32, g).unwrap();
+        let codeword = poly.evaluate_coset(&domain);
+
+        let mut transcript = ProofStream::new();
+        let (final_polynomial, fri_layers) =
+            fri_commit(codeword, &mut transcript, &domain, &params);
+        let pow_nonce = fri_grind(&mut transcript, &params);
+        let decommitments = fri_query_phase(&fri_layers, &mut transcript, &params);
+
+        (
+            FriProof::new(&fri_layers, final_polynomial, pow_nonce, decommitments),
+            domain,
+            params,
+        )
+    }
+
+    #[test]
+    fn fri_proof_round_trips_through_bytes() {
+        let (proof, _, _) = sample_proof();
+        let decoded = FriProof::from_bytes(&proof.to_bytes()).unwrap();
+
+        assert_eq!(decoded.layer_roots, proof.layer_roots);
+        assert_eq!(decoded.final_polynomial, proof.final_polynomial);
+        assert_eq!(decoded.pow_nonce, proof.pow_nonce);
+        assert_eq!(decoded.decommitments.len(), proof.decommitments.len());
+        assert_eq!(
+            decoded.decommitments[0].layers_evaluations,
+            proof.decommitments[0].layers_evaluations
+        );
+    }
+
+    #[test]
+    fn fri_commit_squares_the_domain() {
+        let field = Field::new(97);
+        let poly = Polynomial::new((1..9).map(|c| FieldElement::new(c, field)).collect());
+        let g = FieldElement::new(28, field);
+        let offset = FieldElement::new(5, field);
+        let domain = EvaluationDomain::new(32, g, offset).unwrap();
+
+        let mut transcript = ProofStream::new();
+        let codeword = poly.evaluate_coset(&domain);
+        let (_, fri_layers) =
+            fri_commit(codeword, &mut transcript, &domain, &sha256_params(8, 4, 2));
+
+        assert_eq!(fri_layers.len(), 3);
+        for (i, pair) in fri_layers.windows(2).enumerate() {
+            assert_eq!(pair[1].evaluations.len(), 32 >> (i + 1));
+            for (j, x) in pair[1].domain.iter().enumerate() {
+                let y = pair[0].domain.element(j);
+                assert_eq!(x, y * y);
+            }
+        }
+    }
+
+    #[test]
+    fn verify_fri_accepts_honest_proof() {
+        let (proof, domain, params) = sample_proof();
+
+        assert!(verify_fri(&proof, &domain, &params).is_ok());
+    }
+
+    #[test]
+    fn verify_fri_rejects_tampered_evaluation() {
+        let (mut proof, domain, params) = sample_proof();
+        let field = domain.field();
+        let eval = proof.decommitments[0].layers_evaluations[0][0];
+        proof.decommitments[0].layers_evaluations[0][0] = eval + field.one();
+
+        assert!(verify_fri(&proof, &domain, &params).is_err());
+    }
+
+    #[test]
+    fn verify_fri_rejects_missing_layers() {
+        let (mut proof, domain, params) = sample_proof();
+        proof.layer_roots.pop();
+
+        assert_eq!(
+            verify_fri(&proof, &domain, &params),
+            Err(VerificationError::LayerCountMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn verify_fri_rejects_missing_queries() {
+        let (mut proof, domain, params) = sample_proof();
+        proof.decommitments.pop();
+
+        assert_eq!(
+            verify_fri(&proof, &domain, &params),
+            Err(VerificationError::QueryCountMismatch {
+                expected: 4,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn verify_fri_accepts_higher_folding_arity() {
+        // 32 points fold to 8 and then to 2 with arity 4, and straight to 2 with arity 16.
+        let (proof, domain, params) = sample_proof_with_params(sha256_params(16, 2, 4));
+        assert!(verify_fri(&proof, &domain, &params).is_ok());
+        assert_eq!(proof.decommitments[0].layers_evaluations[0].len(), 4);
+
+        let (proof, domain, params) = sample_proof_with_params(sha256_params(16, 2, 16));
+        assert!(verify_fri(&proof, &domain, &params).is_ok());
+        assert!(verify_fri(&proof, &domain, &sha256_params(16, 2, 2)).is_err());
+    }
+
+    #[test]
+    fn verify_fri_rejects_tampered_sibling_with_higher_arity() {
+        let (mut proof, domain, params) = sample_proof_with_params(sha256_params(16, 2, 4));
+        let field = domain.field();
+        let eval = proof.decommitments[1].layers_evaluations[1][3];
+        proof.decommitments[1].layers_evaluations[1][3] = eval + field.one();
+
+        assert!(verify_fri(&proof, &domain, &params).is_err());
+    }
+
+    #[test]
+    fn verify_fri_checks_the_proof_of_work() {
+        let params = FriParams::new(8, 4, 2, 4, 0, 10, HashFunction::Sha256).unwrap();
+        let (mut proof, domain, params) = sample_proof_with_params(params);
+        assert!(verify_fri(&proof, &domain, &params).is_ok());
+
+        proof.pow_nonce += 1;
+        assert_eq!(
+            verify_fri(&proof, &domain, &params),
+            Err(VerificationError::ProofOfWork)
+        );
+    }
+
+    #[test]
+    fn fold_codeword_matches_fold_polynomial() {
+        let field = Field::new(97);
+        let poly = Polynomial::new((1..12).map(|c| FieldElement::new(c * 7, field)).collect());
+        let alpha = FieldElement::new(13, field);
+        let domain = EvaluationDomain::from_size(32, field.generator()).unwrap();
+        let codeword = poly.evaluate_coset(&domain);
+
+        for folding_factor in [2, 4, 8, 16] {
+            let folded = fold_codeword(&codeword, &domain, &alpha, folding_factor);
+            let expected = crate::fold_polynomial(&poly, &alpha, folding_factor)
+                .evaluate_coset(&domain.fold(folding_factor));
+
+            assert_eq!(folded, expected);
+        }
+    }
+
+    #[test]
+    fn fri_commit_folds_a_low_degree_codeword() {
+        let (_, _, domain, codeword) = low_degree_codeword();
+
+        let mut transcript = ProofStream::new();
+        let (_, fri_layers) =
+            fri_commit(codeword, &mut transcript, &domain, &sha256_params(8, 4, 2));
+
+        // the last layer is committed after two folds, dividing the degree bound from 8 to 2
+        let last = fri_layers.last().unwrap();
+        let last_poly = Polynomial::interpolate(&last.domain, &last.evaluations);
+        assert_eq!(last.evaluations.len(), 8);
+        assert!(last_poly.degree() <= Some(1));
+    }
+
+    #[test]
+    fn low_degree_codeword_is_accepted() {
+        let (_, _, domain, codeword) = low_degree_codeword();
+
+        for folding_factor in [2, 8] {
+            let params = sha256_params(8, 4, folding_factor);
+            let proof = prove_low_degree(codeword.clone(), &domain, &params).unwrap();
+            assert!(verify_fri(&proof, &domain, &params).is_ok());
+        }
+
+        // the same proof does not vouch for a smaller degree bound
+        let proof = prove_low_degree(codeword, &domain, &sha256_params(8, 4, 2)).unwrap();
+        assert!(verify_fri(&proof, &domain, &sha256_params(4, 8, 2)).is_err());
+    }
+
+    #[test]
+    fn far_from_code_codeword_is_rejected() {
+        let (domain, codeword) = far_codeword();
+
+        for params in [
+            sha256_params(8, 4, 2),
+            sha256_params(4, 8, 4),
+            sha256_params(16, 2, 4),
+        ] {
+            let proof = prove_low_degree(codeword.clone(), &domain, &params).unwrap();
+            assert!(verify_fri(&proof, &domain, &params).is_err());
+        }
+    }
+
+    #[test]
+    fn deep_sampling_proofs_are_checked_against_the_codeword() {
+        let (field, _, domain, codeword) = low_degree_codeword();
+        let params = sha256_params(8, 4, 2).with_deep_sampling();
+
+        let proof = prove_low_degree(codeword.clone(), &domain, &params).unwrap();
+        let received = FriProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(verify_fri(&received, &domain, &params).is_ok());
+        assert_eq!(received.deep.as_ref().unwrap().openings.len(), 4);
+
+        // the proof only verifies in the mode it was produced in
+        assert_eq!(
+            verify_fri(&proof, &domain, &sha256_params(8, 4, 2)),
+            Err(VerificationError::DeepStepMismatch)
+        );
+        let plain = prove_low_degree(codeword, &domain, &sha256_params(8, 4, 2)).unwrap();
+        assert!(verify_fri(&plain, &domain, &params).is_err());
+
+        // a wrong out-of-domain value makes the quotient high degree
+        let mut forged = proof.clone();
+        let deep = forged.deep.as_mut().unwrap();
+        deep.ood_evaluation = deep.ood_evaluation + field.one();
+        assert!(verify_fri(&forged, &domain, &params).is_err());
+
+        // the openings must match the commitment to the original codeword
+        let mut tampered = proof;
+        let opening = &mut tampered.deep.as_mut().unwrap().openings[1];
+        opening.evaluations[0] = opening.evaluations[0] + field.one();
+        assert!(verify_fri(&tampered, &domain, &params).is_err());
+    }
+
+    #[test]
+    fn deep_sampling_rejects_far_from_code_codeword() {
+        let (domain, codeword) = far_codeword();
+
+        for params in [sha256_params(8, 4, 2), sha256_params(4, 8, 4)] {
+            let params = params.with_deep_sampling();
+            let proof = prove_low_degree(codeword.clone(), &domain, &params).unwrap();
+            assert!(verify_fri(&proof, &domain, &params).is_err());
+        }
+    }
+
+    #[test]
+    fn low_degree_inputs_are_validated() {
+        let field = Field::new(97);
+        let domain = EvaluationDomain::from_size(32, field.generator()).unwrap();
+
+        assert_eq!(
+            prove_low_degree(vec![field.zero(); 16], &domain, &sha256_params(8, 4, 2)).unwrap_err(),
+            FriError::CodewordLengthMismatch {
+                expected: 32,
+                found: 16
+            }
+        );
+        assert_eq!(
+            prove_low_degree(vec![field.zero(); 32], &domain, &sha256_params(8, 2, 2)).unwrap_err(),
+            FriError::DomainSizeMismatch {
+                expected: 16,
+                found: 32
+            }
+        );
+    }
+
+    #[test]
+    fn early_stopping_sends_the_final_polynomial() {
+        let (_, _, domain, codeword) = low_degree_codeword();
+        let full = prove_low_degree(codeword.clone(), &domain, &sha256_params(8, 4, 2)).unwrap();
+
+        // folding 8 by 4 once leaves a line, and by 2 once leaves a cubic
+        for (folding_factor, final_poly_max_degree) in [(4, 1), (2, 3)] {
+            let params = FriParams::new(
+                8,
+                4,
+                folding_factor,
+                4,
+                final_poly_max_degree,
+                0,
+                HashFunction::Sha256,
+            )
+            .unwrap();
+            let proof = prove_low_degree(codeword.clone(), &domain, &params).unwrap();
+
+            assert_eq!(proof.layer_roots.len(), 1);
+            assert!(proof.final_polynomial.coeffs.len() <= final_poly_max_degree + 1);
+            assert!(proof.to_bytes().len() < full.to_bytes().len());
+            assert!(verify_fri(&proof, &domain, &params).is_ok());
+        }
+    }
+
+    #[test]
+    fn verify_fri_rejects_tampered_final_polynomial() {
+        let (field, _, domain, codeword) = low_degree_codeword();
+        let params = FriParams::new(8, 4, 2, 4, 3, 0, HashFunction::Sha256).unwrap();
+        let proof = prove_low_degree(codeword, &domain, &params).unwrap();
+
+        let mut tampered = proof.clone();
+        tampered.final_polynomial.coeffs[0] = tampered.final_polynomial.coeffs[0] + field.one();
+        assert!(verify_fri(&tampered, &domain, &params).is_err());
+
+        // a final polynomial above the final degree bound is rejected outright
+        let mut tampered = proof;
+        tampered.final_polynomial.coeffs.resize(5, field.one());
+        assert!(verify_fri(&tampered, &domain, &params).is_err());
+    }
+
+    #[test]
+    fn verify_fri_rejects_forged_final_value() {
+        let (field, _, domain, codeword) = low_degree_codeword();
+        let params = sha256_params(8, 4, 2);
+
+        let mut transcript = ProofStream::new();
+        let (final_polynomial, fri_layers) =
+            fri_commit(codeword, &mut transcript, &domain, &params);
+
+        // A cheating prover commits the honest layers but claims another constant, deriving
+        // the queries from a transcript consistent with the forged value.
+        let forged = Polynomial::new(vec![final_polynomial.coeffs[0] + field.one()]);
+        let mut transcript = ProofStream::new();
+        for (i, layer) in fri_layers.iter().enumerate() {
+            transcript.absorb(&root_label(i), layer.merkle_tree.root_hash());
+            transcript.squeeze_field_element(&alpha_label(i), &field.one());
+        }
+        transcript.absorb(FINAL_POLYNOMIAL_LABEL, &final_polynomial_bytes(&forged));
+        let pow_nonce = fri_grind(&mut transcript, &params);
+        let decommitments = fri_query_phase(&fri_layers, &mut transcript, &params);
+        let proof = FriProof::new(&fri_layers, forged, pow_nonce, decommitments);
+
+        assert!(verify_fri(&proof, &domain, &params).is_err());
+    }
+
+    #[test]
+    fn verify_fri_rejects_non_canonical_final_value() {
+        let (proof, domain, params) = sample_proof();
+        let field = domain.field();
+        assert!(verify_fri(&proof, &domain, &params).is_ok());
+
+        // the same constant, shifted by the prime
+        let mut shifted = proof.clone();
+        shifted.final_polynomial.coeffs[0].num += 97;
+        assert!(verify_fri(&shifted, &domain, &params).is_err());
+
+        // an element of another field
+        let mut foreign = proof.clone();
+        foreign.final_polynomial.coeffs[0].field = Field::new(193);
+        assert!(verify_fri(&foreign, &domain, &params).is_err());
+
+        // a trailing zero coefficient, still within the final degree bound of 4
+        let poly = Polynomial::new((1..5).map(|c| FieldElement::new(c, field)).collect());
+        let (domain, codeword) = low_degree_extend(&poly, 8, field.generator()).unwrap();
+        let params = FriParams::new(8, 4, 2, 4, 3, 0, HashFunction::Sha256).unwrap();
+        let proof = prove_low_degree(codeword, &domain, &params).unwrap();
+        assert!(verify_fri(&proof, &domain, &params).is_ok());
+
+        let mut padded = proof;
+        padded.final_polynomial.coeffs.push(field.zero());
+        assert!(padded.final_polynomial.coeffs.len() <= params.final_degree_bound());
+        assert!(verify_fri(&padded, &domain, &params).is_err());
+    }
+
+    #[test]
+    fn verify_fri_rejects_opened_values_outside_the_field() {
+        let (proof, domain, params) = sample_proof();
+        let bytes = proof.to_bytes();
 
-        let layer = FriLayer::new(&poly, domain);
+        // the Merkle proofs only bind the value, so a forged field would reach the folding arithmetic
+        let mut json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        json["decommitments"][0]["layers_evaluations"][1][0]["field"]["prime"] = 193.into();
+        let foreign = FriProof::from_bytes(&serde_json::to_vec(&json).unwrap()).unwrap();
+        assert_eq!(
+            verify_fri(&foreign, &domain, &params),
+            Err(VerificationError::NonReducedElement)
+        );
 
-        assert!(!layer.polynomial.coeffs.is_empty());
+        // the same value, shifted by the prime
+        let mut json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let value = &mut json["decommitments"][2]["layers_evaluations"][0][1]["num"];
+        *value = (value.as_i64().unwrap() + 97).into();
+        let shifted = FriProof::from_bytes(&serde_json::to_vec(&json).unwrap()).unwrap();
+        assert_eq!(
+            verify_fri(&shifted, &domain, &params),
+            Err(VerificationError::NonReducedElement)
+        );
     }
 }
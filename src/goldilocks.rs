@@ -0,0 +1,283 @@
+use crate::{Hashable, PrimeField};
+use ring::digest::Context;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// The `Goldilocks` struct is an element of the prime field of modulus `p = 2^64 - 2^32 + 1`.
+/// As `2^64 = 2^32 - 1` and `2^96 = -1` modulo `p`, a 128-bit product reduces with a few additions and
+/// subtractions, and the 2^32 two-adicity of `p - 1` supports domains of up to 2^32 points.
+/// Values are always kept canonical, in `[0, p)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Goldilocks {
+    value: u64, // The canonical value of the element.
+}
+
+impl Goldilocks {
+    // The prime modulus `2^64 - 2^32 + 1`.
+    pub const MODULUS: u64 = 0xFFFF_FFFF_0000_0001;
+
+    // `2^64 mod p`, the correction applied whenever a value wraps around 2^64.
+    const EPSILON: u64 = 0xFFFF_FFFF;
+
+    // The smallest generator of the multiplicative group.
+    pub const GENERATOR: Goldilocks = Goldilocks { value: 7 };
+
+    // The largest `k` such that `2^k` divides `p - 1`.
+    pub const TWO_ADICITY: u32 = 32;
+
+    // A primitive root of unity of order 2^32, `GENERATOR^((p - 1) / 2^32)`.
+    pub const TWO_ADIC_ROOT_OF_UNITY: Goldilocks = Goldilocks {
+        value: 1_753_635_133_440_165_772,
+    };
+
+    // Creates the element `n mod p`.
+    pub const fn new(n: u64) -> Self {
+        Self {
+            value: Self::canonicalize(n),
+        }
+    }
+
+    // Returns the canonical value of the element, in `[0, p)`.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    // Returns a primitive root of unity of order `2^log_size`, for `log_size` up to the two-adicity.
+    pub fn root_of_unity(log_size: u32) -> Option<Self> {
+        (log_size <= Self::TWO_ADICITY)
+            .then(|| Self::TWO_ADIC_ROOT_OF_UNITY.pow(1u128 << (Self::TWO_ADICITY - log_size)))
+    }
+
+    // Maps any `u64` to `[0, p)`; a single subtraction suffices as `2^64 < 2p`.
+    const fn canonicalize(n: u64) -> u64 {
+        if n >= Self::MODULUS {
+            n - Self::MODULUS
+        } else {
+            n
+        }
+    }
+
+    // Reduces a 128-bit value modulo `p`. Writing `x = x_lo + 2^64 x_mid + 2^96 x_hi` with 32-bit
+    // `x_mid` and `x_hi`, we have `x = x_lo + (2^32 - 1) x_mid - x_hi mod p`.
+    fn reduce(x: u128) -> u64 {
+        let x_lo = x as u64;
+        let x_mid = (x >> 64) as u64 & Self::EPSILON;
+        let x_hi = (x >> 96) as u64;
+
+        // borrowing 2^64 adds `p - 2^64 = -EPSILON`, and the wrapped value is above EPSILON
+        let (t0, borrow) = x_lo.overflowing_sub(x_hi);
+        let t0 = if borrow { t0 - Self::EPSILON } else { t0 };
+
+        // `x_mid * EPSILON < 2^64 - 2^33`, so adding EPSILON after a carry cannot overflow again
+        let (t1, carry) = t0.overflowing_add(x_mid * Self::EPSILON);
+        let t1 = if carry { t1 + Self::EPSILON } else { t1 };
+
+        Self::canonicalize(t1)
+    }
+}
+
+// Implements the `PrimeField` trait so FRI can run over `Goldilocks`; the modulus is fixed, so `self` is unused.
+impl PrimeField for Goldilocks {
+    fn zero(&self) -> Self {
+        Goldilocks { value: 0 }
+    }
+
+    fn one(&self) -> Self {
+        Goldilocks { value: 1 }
+    }
+
+    fn integer(&self, n: u64) -> Self {
+        Goldilocks::new(n)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
+    fn is_reduced(&self) -> bool {
+        self.value < Self::MODULUS
+    }
+
+    fn group_order(&self) -> u128 {
+        Self::MODULUS as u128 - 1
+    }
+
+    fn generator(&self) -> Self {
+        Self::GENERATOR
+    }
+
+    // The canonical value as 8 big-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.value.to_be_bytes().to_vec()
+    }
+
+    // 128 bits of the input are reduced modulo the prime, a bias below 2^-64.
+    fn sample(&self, bytes: &[u8]) -> Self {
+        let mut array = [0u8; 16];
+        array.copy_from_slice(&bytes[0..16]);
+
+        Goldilocks {
+            value: (u128::from_be_bytes(array) % Self::MODULUS as u128) as u64,
+        }
+    }
+}
+
+// Implements the `Hashable` trait to make `Goldilocks` usable in a Merkle tree.
+impl Hashable for Goldilocks {
+    fn update_context(&self, context: &mut Context) {
+        context.update(&self.value.to_le_bytes());
+    }
+}
+
+// Implements the `Add` trait; a carry out of 64 bits is worth `2^64 = EPSILON mod p`.
+impl Add for Goldilocks {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let (sum, carry) = self.value.overflowing_add(rhs.value);
+        let sum = if carry { sum + Self::EPSILON } else { sum };
+
+        Goldilocks::new(sum)
+    }
+}
+
+// Implements the `Sub` trait; a borrow of `2^64` is corrected by subtracting `EPSILON`.
+impl Sub for Goldilocks {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let (difference, borrow) = self.value.overflowing_sub(rhs.value);
+        let value = if borrow {
+            difference - Self::EPSILON
+        } else {
+            difference
+        };
+
+        Goldilocks { value }
+    }
+}
+
+// Implements the `Mul` trait with the special-form reduction of the 128-bit product.
+impl Mul for Goldilocks {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Goldilocks {
+            value: Goldilocks::reduce(self.value as u128 * rhs.value as u128),
+        }
+    }
+}
+
+// Implements the `Div` trait for division of `Goldilocks` elements.
+impl Div for Goldilocks {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(rhs.value != 0, "divide by 0");
+
+        self * rhs.inverse()
+    }
+}
+
+// Implements the `Neg` trait for negation of `Goldilocks` elements.
+impl Neg for Goldilocks {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Goldilocks::new(Self::MODULUS - self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::montgomery::testing::{assert_fri_round_trip, assert_matches_montgomery};
+    use crate::MontgomeryField;
+
+    #[test]
+    fn arithmetic_matches_the_montgomery_field() {
+        assert_matches_montgomery(Goldilocks::new(1), Goldilocks::MODULUS, |x| x.value());
+        assert_eq!(
+            Goldilocks::new(Goldilocks::EPSILON).value(),
+            Goldilocks::EPSILON
+        );
+    }
+
+    #[test]
+    fn reduce_handles_the_full_128_bit_range() {
+        let p = Goldilocks::MODULUS as u128;
+        for x in [
+            0,
+            p,
+            p * p - 1,
+            u128::MAX,
+            u128::MAX - p,
+            1 << 96,
+            (1 << 96) - 1,
+        ] {
+            assert_eq!(Goldilocks::reduce(x) as u128, x % p);
+        }
+    }
+
+    #[test]
+    fn generator_and_roots_of_unity() {
+        let one = Goldilocks::new(1);
+        let order = one.group_order();
+        assert_eq!(one.two_adicity(), Goldilocks::TWO_ADICITY);
+        assert_eq!(
+            Goldilocks::GENERATOR.value(),
+            MontgomeryField::new(Goldilocks::MODULUS)
+                .generator()
+                .value()
+        );
+        assert_eq!(
+            Goldilocks::GENERATOR.pow(order >> Goldilocks::TWO_ADICITY),
+            Goldilocks::TWO_ADIC_ROOT_OF_UNITY
+        );
+
+        for log_size in [0, 1, 5, 17, 32] {
+            let root = Goldilocks::root_of_unity(log_size).unwrap();
+            assert_eq!(root, one.primitive_nth_root(1 << log_size).unwrap());
+            assert_eq!(root.pow(1 << log_size), one);
+            if log_size > 0 {
+                assert_eq!(root.pow(1 << (log_size - 1)), -one);
+            }
+        }
+        assert_eq!(Goldilocks::root_of_unity(33), None);
+    }
+
+    #[test]
+    fn encoding_is_canonical() {
+        let x = Goldilocks::new(Goldilocks::MODULUS + 5);
+        assert_eq!(x.value(), 5);
+        assert_eq!(x.to_bytes(), 5u64.to_be_bytes().to_vec());
+        assert!(x.is_reduced());
+
+        let forged: Goldilocks =
+            serde_json::from_str(&format!("{{\"value\":{}}}", Goldilocks::MODULUS + 5)).unwrap();
+        assert!(!forged.is_reduced());
+    }
+
+    #[test]
+    fn fri_proofs_agree_with_the_montgomery_field() {
+        let proof = assert_fri_round_trip(Goldilocks::new(1));
+
+        // both implementations encode and hash the same canonical values, so the proofs are identical
+        let generic = assert_fri_round_trip(MontgomeryField::new(Goldilocks::MODULUS).one());
+        assert_eq!(proof.layer_roots, generic.layer_roots);
+        assert_eq!(
+            proof
+                .final_polynomial
+                .coeffs
+                .iter()
+                .map(Goldilocks::value)
+                .collect::<Vec<_>>(),
+            generic
+                .final_polynomial
+                .coeffs
+                .iter()
+                .map(|c| c.value())
+                .collect::<Vec<_>>()
+        );
+    }
+}
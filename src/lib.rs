@@ -1,10 +1,42 @@
+pub mod babybear;
+pub mod batch;
+pub mod deep;
+pub mod domain;
+pub mod error;
+pub mod extension;
+pub mod extension_fri;
 pub mod finite_field;
 pub mod fri;
+pub mod goldilocks;
+pub mod merkle_tree;
+pub mod mersenne31;
+pub mod montgomery;
+pub mod ntt;
+pub mod params;
+pub mod pcs;
 pub mod polynomial;
+pub mod prime_field;
 pub mod prover;
+pub mod soundness;
 
 // public re-export
+pub use babybear::*;
+pub use batch::*;
+pub use deep::*;
+pub use domain::*;
+pub use error::*;
+pub use extension::*;
+pub use extension_fri::*;
 pub use finite_field::*;
 pub use fri::*;
+pub use goldilocks::*;
+pub use merkle_tree::*;
+pub use mersenne31::*;
+pub use montgomery::*;
+pub use ntt::*;
+pub use params::*;
+pub use pcs::*;
 pub use polynomial::*;
+pub use prime_field::*;
 pub use prover::*;
+pub use soundness::*;
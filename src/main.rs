@@ -1,5 +1,6 @@
 use frs_iopp::{
-    fri_commit, fri_query_phase, verify_fri, Field, FieldElement, Polynomial, ProofStream,
+    fri_commit, fri_grind, fri_query_phase, low_degree_extend, verify_fri, Field, FieldElement,
+    FriParams, FriProof, HashFunction, Polynomial, ProofStream, SecurityEstimate,
 };
 
 fn main() {
@@ -19,29 +20,46 @@ fn main() {
     let g = FieldElement::new(10, field);
     let h = FieldElement::new(0, field);
 
-    // value 28 obtained based on an example in a course taken
-    let domain = vec![
-        FieldElement::new(28, field),
-        FieldElement::new(28i128.pow(2), field),
-        FieldElement::new(28i128.pow(4), field),
-        FieldElement::new(28i128.pow(8), field),
-    ];
-
     let poly = Polynomial::new(vec![a, b, c, d, e, f, g, h]);
+
+    // degree bound 8 at rate 1/4, folding by 2 with 10 queries, stopping once the polynomial is a line,
+    // and 8 bits of proof-of-work; the number of layers is derived from these
+    let params =
+        FriParams::new(8, 4, 2, 10, 1, 8, HashFunction::Sha256).expect("Invalid parameters");
+
+    // Reed-Solomon encode the polynomial at rate 1/4 over a coset of the field's generator:
+    // degree bound 8 times blowup 4 gives a domain of 32 points
+    let (domain, codeword) = low_degree_extend(&poly, params.blowup(), field.generator())
+        .expect("Invalid blowup factor");
+
     let mut transcript = ProofStream::new();
-    let num_layer = 3; // this is based on the equation
-    let number_of_queries = 10;
 
-    println!("Initial eveluation Domain size: {}", domain.len());
+    println!("Initial eveluation Domain size: {}", domain.size);
+    println!(
+        "Blowup factor: {} (codeword of {} values)",
+        params.blowup(),
+        codeword.len()
+    );
+    println!(
+        "Folding factor: {} ({} layers)",
+        params.folding_factor(),
+        params.number_layers()
+    );
     println!("Prime field: {}", prime);
+    let security = SecurityEstimate::new(&params, (prime as f64).log2());
+    println!(
+        "Security: {:.1} bits conjectured, {:.1} bits provable",
+        security.conjectured_bits, security.provable_bits
+    );
     println!();
 
     // commit phase
-    let (last_value, fri_layers) = fri_commit(num_layer, poly, &mut transcript, &domain);
+    let (final_polynomial, fri_layers) = fri_commit(codeword, &mut transcript, &domain, &params);
 
-    // displaying the results of the folding and mixing
+    // displaying the results of the folding and mixing, interpolating each committed codeword
     for (i, val) in fri_layers.iter().enumerate() {
-        let res = val.polynomial.clone();
+        let mut res = Polynomial::interpolate(&val.domain, &val.evaluations);
+        res.normalize();
 
         print!("Polyomial - {}: ", i);
         for x in res.coeffs {
@@ -51,28 +69,29 @@ fn main() {
     }
 
     println!();
-    println!("Last value: {:?}", last_value);
+    print!("Final polynomial: ");
+    for x in final_polynomial.coeffs.iter() {
+        print!("{}, ", x.num);
+    }
+    println!();
     println!();
 
-    // query phase
-    // this is one of the roots of unity in the domain
-    let nth_root_of_unit = FieldElement::new(28i128.pow(2), field);
+    // proof-of-work, then query phase
+    let pow_nonce = fri_grind(&mut transcript, &params);
+    let decommitments = fri_query_phase(&fri_layers, &mut transcript, &params);
 
-    let decommitments = fri_query_phase(
-        nth_root_of_unit,
-        domain.len(),
+    // bundle the commitments and decommitments into a proof and ship it as bytes
+    let proof = FriProof::new(
         &fri_layers,
-        &mut transcript,
-        number_of_queries,
+        final_polynomial,
+        pow_nonce,
+        decommitments.clone(),
     );
+    let proof_bytes = proof.to_bytes();
 
-    // verifier phase
-    let verified = verify_fri(
-        &fri_layers,
-        &decommitments,
-        &mut transcript,
-        nth_root_of_unit,
-    );
+    // verifier phase, using only the received bytes and the public parameters
+    let received_proof = FriProof::from_bytes(&proof_bytes).expect("Invalid proof bytes");
+    let verified = verify_fri(&received_proof, &domain, &params);
 
     // display results
     println!("COMMIT PHASE: ");
@@ -82,22 +101,20 @@ fn main() {
     println!();
 
     println!("QUERY PHASE: ");
-    println!("g (from verfier): {}", nth_root_of_unit.num);
+    println!("g (from verfier): {}", domain.generator.num);
 
     for (i, query) in decommitments.iter().enumerate() {
-        let layers: Vec<i128> = query.layers_evaluations.iter().map(|l| l.num).collect();
-        let layer_sym: Vec<i128> = query.layers_evaluations_sym.iter().map(|l| l.num).collect();
-        println!(
-            "Layer {} evaluation at {} and {}: {:?}",
-            i, nth_root_of_unit.num, -nth_root_of_unit.num, layers
-        );
-        println!(
-            "Layer {} evaluation symetric at {} and {}: {:?}",
-            i, nth_root_of_unit.num, -nth_root_of_unit.num, layer_sym
-        );
+        for (j, layer) in query.layers_evaluations.iter().enumerate() {
+            let coset: Vec<i128> = layer.iter().map(|l| l.num).collect();
+            println!("Query {} layer {} coset evaluations: {:?}", i, j, coset);
+        }
         println!();
     }
 
     println!("VERIFICATION PHASE: ");
-    println!("Verified commit? - {}", verified);
+    println!("Proof size: {} bytes", proof_bytes.len());
+    match verified {
+        Ok(()) => println!("Verified commit? - true"),
+        Err(error) => println!("Verified commit? - false ({})", error),
+    }
 }
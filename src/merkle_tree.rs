@@ -0,0 +1,254 @@
+use ring::digest::{digest, Algorithm, Context};
+use serde::{Deserialize, Serialize};
+
+// Prefixes separating leaf hashes from inner node hashes, so a node can never be opened as a leaf.
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// The `Hashable` trait must be implemented by values stored in a `MerkleTree`,
+/// it feeds the value's canonical bytes into a hashing context.
+pub trait Hashable {
+    fn update_context(&self, context: &mut Context);
+}
+
+// A vector is hashed as the concatenation of its elements, so that a leaf can hold
+// several values opened together, such as the evaluations folded into one point.
+impl<T: Hashable> Hashable for Vec<T> {
+    fn update_context(&self, context: &mut Context) {
+        for value in self {
+            value.update_context(context);
+        }
+    }
+}
+
+/// The `MerkleTree` struct commits to a vector of values.
+/// All levels are kept as flat vectors of hashes, from the leaves up to the root, so building
+/// the tree costs one hash per node. When a level has an odd number of nodes, the last one
+/// is promoted unchanged to the next level.
+/// The hashes, and therefore the roots, are those of the `merkle` crate, which this tree replaces:
+/// its `from_vec` takes quadratic time by removing nodes from the front of a vector, and its
+/// `Hashable` cannot be implemented for leaves holding several field elements.
+#[derive(Clone, Debug)]
+pub struct MerkleTree<T> {
+    values: Vec<T>,
+    levels: Vec<Vec<Vec<u8>>>,
+    root: Vec<u8>,
+}
+
+impl<T: Hashable> MerkleTree<T> {
+    // Builds the tree over `values`, hashing each level pairwise until a single root remains.
+    pub fn from_vec(algorithm: &'static Algorithm, values: Vec<T>) -> Self {
+        let leaves: Vec<Vec<u8>> = values.iter().map(|v| leaf_hash(algorithm, v)).collect();
+
+        let mut levels = vec![leaves];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(algorithm, left, right),
+                    [single] => single.clone(),
+                    _ => unreachable!("chunks of two"),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        // The root of an empty tree is the hash of the empty string.
+        let root = match levels[levels.len() - 1].first() {
+            Some(root) => root.clone(),
+            None => digest(algorithm, &[]).as_ref().to_vec(),
+        };
+
+        Self {
+            values,
+            levels,
+            root,
+        }
+    }
+
+    // Returns the root hash of the tree.
+    pub fn root_hash(&self) -> &Vec<u8> {
+        &self.root
+    }
+
+    // Returns the number of leaves in the tree.
+    pub fn count(&self) -> usize {
+        self.values.len()
+    }
+
+    // Returns whether the tree has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    // Generates an inclusion proof for the `n`-th leaf, or `None` if there is no such leaf.
+    pub fn gen_nth_proof(&self, n: usize) -> Option<Proof<T>>
+    where
+        T: Clone,
+    {
+        let value = self.values.get(n)?.clone();
+
+        let mut path = vec![];
+        let mut index = n;
+        for level in self.levels.iter().take(self.levels.len() - 1) {
+            if let Some(sibling) = level.get(index ^ 1) {
+                path.push(sibling.clone());
+            }
+            index /= 2;
+        }
+
+        Some(Proof {
+            index: n,
+            value,
+            path,
+        })
+    }
+}
+
+/// The `Proof` struct is an inclusion proof for the leaf at `index`:
+/// the leaf value and the sibling hashes on the path from that leaf up to the root.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Proof<T> {
+    pub index: usize,       // Position of the opened leaf.
+    pub value: T,           // Value stored in the opened leaf.
+    pub path: Vec<Vec<u8>>, // Sibling hashes, from the leaf level upwards.
+}
+
+impl<T: Hashable> Proof<T> {
+    // Checks that the proof opens `value` at `index` in a tree of `count` leaves with root `root_hash`.
+    // The shape of the tree, and therefore which levels have a sibling, is derived from `count`.
+    pub fn validate(&self, algorithm: &'static Algorithm, root_hash: &[u8], count: usize) -> bool {
+        if self.index >= count {
+            return false;
+        }
+
+        let mut hash = leaf_hash(algorithm, &self.value);
+        let mut path = self.path.iter();
+        let mut index = self.index;
+        let mut size = count;
+        while size > 1 {
+            if (index ^ 1) < size {
+                let sibling = match path.next() {
+                    Some(sibling) => sibling,
+                    None => return false,
+                };
+                hash = if index.is_multiple_of(2) {
+                    node_hash(algorithm, &hash, sibling)
+                } else {
+                    node_hash(algorithm, sibling, &hash)
+                };
+            }
+            index /= 2;
+            size = size.div_ceil(2);
+        }
+
+        path.next().is_none() && hash == root_hash
+    }
+}
+
+// Hashes a leaf value, prefixed to separate it from inner nodes.
+fn leaf_hash<T: Hashable>(algorithm: &'static Algorithm, value: &T) -> Vec<u8> {
+    let mut context = Context::new(algorithm);
+    context.update(&[LEAF_PREFIX]);
+    value.update_context(&mut context);
+
+    context.finish().as_ref().to_vec()
+}
+
+// Hashes the concatenation of two child hashes.
+fn node_hash(algorithm: &'static Algorithm, left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut context = Context::new(algorithm);
+    context.update(&[NODE_PREFIX]);
+    context.update(left);
+    context.update(right);
+
+    context.finish().as_ref().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Field, FieldElement};
+    use ring::digest::SHA256;
+
+    fn values(count: i128) -> Vec<FieldElement> {
+        let field = Field::new(97);
+        (0..count).map(|i| FieldElement::new(i, field)).collect()
+    }
+
+    #[test]
+    fn merkle_proofs_validate_for_every_leaf() {
+        for count in [1, 2, 3, 5, 8, 13] {
+            let tree = MerkleTree::from_vec(&SHA256, values(count));
+            let root = tree.root_hash().clone();
+
+            for n in 0..count as usize {
+                let proof = tree.gen_nth_proof(n).unwrap();
+                assert!(proof.validate(&SHA256, &root, count as usize));
+            }
+            assert!(tree.gen_nth_proof(count as usize).is_none());
+        }
+    }
+
+    // A leaf of raw bytes, hashed as the `merkle` crate hashes byte slices.
+    #[derive(Clone)]
+    struct Bytes(Vec<u8>);
+
+    impl Hashable for Bytes {
+        fn update_context(&self, context: &mut Context) {
+            context.update(&self.0);
+        }
+    }
+
+    fn leaves(count: u8) -> Vec<Vec<u8>> {
+        (0..count).map(|i| vec![i; i as usize + 1]).collect()
+    }
+
+    #[test]
+    fn roots_match_the_merkle_crate() {
+        for count in [1, 2, 3, 5, 8, 13] {
+            let leaves = leaves(count);
+            let tree = MerkleTree::from_vec(&SHA256, leaves.iter().cloned().map(Bytes).collect());
+            let reference = merkle::MerkleTree::from_vec(&SHA256, leaves);
+            assert_eq!(tree.root_hash(), reference.root_hash());
+        }
+
+        // the root of the leaves [0], [1, 1], [2, 2, 2], pinned so the commitment format cannot drift
+        let tree = MerkleTree::from_vec(&SHA256, leaves(3).into_iter().map(Bytes).collect());
+        let root: String = tree
+            .root_hash()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert_eq!(
+            root,
+            "bd1a006735cd8a22a057e795ef3eef176b7decaa8cae5f8a30fa7aafe53b23df"
+        );
+    }
+
+    #[test]
+    fn merkle_proofs_reject_tampering() {
+        let tree = MerkleTree::from_vec(&SHA256, values(8));
+        let root = tree.root_hash().clone();
+        let proof = tree.gen_nth_proof(3).unwrap();
+
+        let mut wrong_value = proof.clone();
+        wrong_value.value = wrong_value.value + wrong_value.value.field.one();
+        assert!(!wrong_value.validate(&SHA256, &root, 8));
+
+        let mut wrong_index = proof.clone();
+        wrong_index.index = 2;
+        assert!(!wrong_index.validate(&SHA256, &root, 8));
+
+        let mut short_path = proof.clone();
+        short_path.path.pop();
+        assert!(!short_path.validate(&SHA256, &root, 8));
+
+        assert!(!proof.validate(&SHA256, &root, 16));
+        assert!(!proof.validate(
+            &SHA256,
+            MerkleTree::from_vec(&SHA256, values(7)).root_hash(),
+            8
+        ));
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{Hashable, PrimeField};
+use ring::digest::Context;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// The `Mersenne31` struct is an element of the prime field of modulus `p = 2^31 - 1`, stored in a `u32`.
+/// As `2^31 = 1` modulo `p`, reducing a value only takes adding its bits above 31 to its low 31 bits.
+/// Note that `p - 1 = 2 * 3^2 * 7 * 11 * 31 * 151 * 331` has two-adicity 1: the field has no power-of-two
+/// subgroup beyond `{1, -1}`, so polynomials work over it but FRI domains of size above 2 do not exist.
+/// Using it for FRI needs the circle group of order `p + 1` instead, which this crate does not implement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Mersenne31 {
+    value: u32, // The canonical value of the element.
+}
+
+impl Mersenne31 {
+    // The prime modulus `2^31 - 1`.
+    pub const MODULUS: u32 = 0x7FFF_FFFF;
+
+    // The smallest generator of the multiplicative group.
+    pub const GENERATOR: Mersenne31 = Mersenne31 { value: 7 };
+
+    // The largest `k` such that `2^k` divides `p - 1`.
+    pub const TWO_ADICITY: u32 = 1;
+
+    // Creates the element `n mod p`.
+    pub const fn new(n: u32) -> Self {
+        Self {
+            value: Self::reduce(n as u64),
+        }
+    }
+
+    // Returns the canonical value of the element, in `[0, p)`.
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    // Reduces any `u64` modulo `p` by folding the bits above 31 onto the low ones, as `2^31 = 1 mod p`.
+    // After two folds the value is below `p + 8`, so a single subtraction makes it canonical.
+    const fn reduce(x: u64) -> u32 {
+        let modulus = Self::MODULUS as u64;
+        let x = (x & modulus) + (x >> 31);
+        let x = (x & modulus) + (x >> 31);
+        if x >= modulus {
+            (x - modulus) as u32
+        } else {
+            x as u32
+        }
+    }
+}
+
+// Implements the `PrimeField` trait so polynomials can use `Mersenne31`; the modulus is fixed, so `self` is unused.
+impl PrimeField for Mersenne31 {
+    fn zero(&self) -> Self {
+        Mersenne31 { value: 0 }
+    }
+
+    fn one(&self) -> Self {
+        Mersenne31 { value: 1 }
+    }
+
+    fn integer(&self, n: u64) -> Self {
+        Mersenne31 {
+            value: Mersenne31::reduce(n),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
+    fn is_reduced(&self) -> bool {
+        self.value < Self::MODULUS
+    }
+
+    fn group_order(&self) -> u128 {
+        Self::MODULUS as u128 - 1
+    }
+
+    fn generator(&self) -> Self {
+        Self::GENERATOR
+    }
+
+    // The canonical value as 4 big-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.value.to_be_bytes().to_vec()
+    }
+
+    // 128 bits of the input are reduced modulo the prime, which keeps the bias negligible.
+    fn sample(&self, bytes: &[u8]) -> Self {
+        let mut array = [0u8; 16];
+        array.copy_from_slice(&bytes[0..16]);
+
+        Mersenne31 {
+            value: (u128::from_be_bytes(array) % Self::MODULUS as u128) as u32,
+        }
+    }
+}
+
+// Implements the `Hashable` trait to make `Mersenne31` usable in a Merkle tree.
+impl Hashable for Mersenne31 {
+    fn update_context(&self, context: &mut Context) {
+        context.update(&self.value.to_le_bytes());
+    }
+}
+
+// Implements the `Add` trait; the sum of two reduced values fits in a `u32` as `p < 2^31`.
+impl Add for Mersenne31 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let sum = self.value + rhs.value;
+        let value = if sum >= Self::MODULUS {
+            sum - Self::MODULUS
+        } else {
+            sum
+        };
+
+        Mersenne31 { value }
+    }
+}
+
+// Implements the `Sub` trait for subtraction of `Mersenne31` elements.
+impl Sub for Mersenne31 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let (difference, borrow) = self.value.overflowing_sub(rhs.value);
+        let value = if borrow {
+            difference.wrapping_add(Self::MODULUS)
+        } else {
+            difference
+        };
+
+        Mersenne31 { value }
+    }
+}
+
+// Implements the `Mul` trait with the shift-add reduction of the 62-bit product.
+impl Mul for Mersenne31 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Mersenne31 {
+            value: Mersenne31::reduce(self.value as u64 * rhs.value as u64),
+        }
+    }
+}
+
+// Implements the `Div` trait for division of `Mersenne31` elements.
+impl Div for Mersenne31 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(rhs.value != 0, "divide by 0");
+
+        self * rhs.inverse()
+    }
+}
+
+// Implements the `Neg` trait for negation of `Mersenne31` elements.
+impl Neg for Mersenne31 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Mersenne31 { value: 0 } - self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::montgomery::testing::{assert_matches_montgomery, sample_values};
+    use crate::{EvaluationDomain, FriError, MontgomeryField, Polynomial};
+
+    #[test]
+    fn arithmetic_matches_the_montgomery_field() {
+        let one = Mersenne31::new(1);
+        assert_matches_montgomery(one, Mersenne31::MODULUS as u64, |x| x.value() as u64);
+
+        // `new` reduces any `u32`, including the values above `p`
+        for a in sample_values(Mersenne31::MODULUS as u64) {
+            let a = a as u32;
+            assert_eq!(Mersenne31::new(a), one.integer(a as u64));
+        }
+    }
+
+    #[test]
+    fn polynomials_work_but_power_of_two_domains_stop_at_two() {
+        let one = Mersenne31::new(1);
+        assert_eq!(std::mem::size_of::<Mersenne31>(), 4);
+        assert_eq!(one.two_adicity(), Mersenne31::TWO_ADICITY);
+        assert_eq!(
+            Mersenne31::GENERATOR.value() as u64,
+            MontgomeryField::new(Mersenne31::MODULUS as u64)
+                .generator()
+                .value()
+        );
+
+        let xs: Vec<Mersenne31> = [3, 1 << 20, Mersenne31::MODULUS - 5, 42]
+            .iter()
+            .map(|&x| Mersenne31::new(x))
+            .collect();
+        let ys: Vec<Mersenne31> = [7, 0, 123_456, 9]
+            .iter()
+            .map(|&y| Mersenne31::new(y))
+            .collect();
+        let poly = Polynomial::interpolate_points(&xs, &ys);
+        for (x, y) in xs.iter().zip(ys.iter()) {
+            assert_eq!(poly.evaluate(*x), *y);
+        }
+
+        assert!(EvaluationDomain::from_size(2, Mersenne31::GENERATOR).is_ok());
+        assert_eq!(
+            EvaluationDomain::from_size(4, Mersenne31::GENERATOR).unwrap_err(),
+            FriError::NoRootOfUnity { order: 4 }
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use crate::{smallest_generator, Hashable, PrimeField};
+use ring::digest::Context;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// The `MontgomeryField` struct is a prime field of modulus below 2^64 whose elements are kept in
+/// Montgomery form, `a * R mod p` with `R = 2^64`. A product then reduces with two 64x64-bit
+/// multiplications instead of a division, and every intermediate value fits in a `u128`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MontgomeryField {
+    pub modulus: u64, // The odd prime `p`.
+    r2: u64,          // `R^2 mod p`, which maps an integer to Montgomery form in one reduction.
+    inv: u64,         // `p^-1 mod R`, used by the reduction to clear the low 64 bits.
+}
+
+impl MontgomeryField {
+    // Creates the field of the given prime modulus, precomputing the Montgomery constants.
+    // Any odd prime below 2^64 is supported, including those above 2^63.
+    pub fn new(modulus: u64) -> Self {
+        assert!(
+            modulus > 2 && modulus % 2 == 1,
+            "the Montgomery modulus must be an odd prime"
+        );
+
+        // Newton's iteration doubles the number of correct low bits, and `p` is its own inverse modulo 8
+        let mut inv = modulus;
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(modulus.wrapping_mul(inv)));
+        }
+        let r2 = ((u128::MAX % modulus as u128 + 1) % modulus as u128) as u64;
+
+        Self { modulus, r2, inv }
+    }
+
+    // Returns the element of canonical value `n mod p`.
+    pub fn element(&self, n: u64) -> MontgomeryElement {
+        MontgomeryElement {
+            value: self.reduce(n as u128 * self.r2 as u128),
+            field: *self,
+        }
+    }
+
+    // Returns the additive identity (0) for the field.
+    pub fn zero(&self) -> MontgomeryElement {
+        MontgomeryElement {
+            value: 0,
+            field: *self,
+        }
+    }
+
+    // Returns the multiplicative identity (1) for the field.
+    pub fn one(&self) -> MontgomeryElement {
+        self.element(1)
+    }
+
+    // Returns the order of the multiplicative group, `p - 1`.
+    pub fn group_order(&self) -> u128 {
+        (self.modulus - 1) as u128
+    }
+
+    // Returns a generator of the multiplicative group, the smallest element of order `p - 1`.
+    pub fn generator(&self) -> MontgomeryElement {
+        smallest_generator(&self.one())
+    }
+
+    // Returns true when `element` belongs to this field and is reduced.
+    pub fn contains(&self, element: &MontgomeryElement) -> bool {
+        element.field == *self && element.value < self.modulus
+    }
+
+    // Montgomery reduction: returns `t / R mod p` for `t < p * R`.
+    // With `m = t * p^-1 mod R`, `t - m * p` is a multiple of `R` whose quotient lies in `(-p, p)`,
+    // so only the high halves need to be subtracted.
+    fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.inv);
+        let mp_high = ((m as u128 * self.modulus as u128) >> 64) as u64;
+        let (result, borrow) = ((t >> 64) as u64).overflowing_sub(mp_high);
+        if borrow {
+            result.wrapping_add(self.modulus)
+        } else {
+            result
+        }
+    }
+}
+
+/// The `MontgomeryElement` struct is an element of a `MontgomeryField`, stored in Montgomery form.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MontgomeryElement {
+    value: u64,             // `a * R mod p` for the element `a`.
+    field: MontgomeryField, // The field that this element belongs to.
+}
+
+impl MontgomeryElement {
+    // Returns the canonical value of the element, in `[0, p)`.
+    pub fn value(&self) -> u64 {
+        self.field.reduce(self.value as u128)
+    }
+
+    // Returns the field that this element belongs to.
+    pub fn field(&self) -> MontgomeryField {
+        self.field
+    }
+}
+
+// Implements the `PrimeField` trait so FRI can run over `MontgomeryElement`.
+impl PrimeField for MontgomeryElement {
+    fn zero(&self) -> Self {
+        self.field.zero()
+    }
+
+    fn one(&self) -> Self {
+        self.field.one()
+    }
+
+    fn integer(&self, n: u64) -> Self {
+        self.field.element(n)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
+    fn is_reduced(&self) -> bool {
+        self.value < self.field.modulus
+    }
+
+    fn group_order(&self) -> u128 {
+        self.field.group_order()
+    }
+
+    fn generator(&self) -> Self {
+        self.field.generator()
+    }
+
+    // The canonical value as 8 big-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.value().to_be_bytes().to_vec()
+    }
+
+    // 128 bits of the input are reduced modulo the prime, a bias below 2^-64.
+    fn sample(&self, bytes: &[u8]) -> Self {
+        let mut array = [0u8; 16];
+        array.copy_from_slice(&bytes[0..16]);
+        let n = u128::from_be_bytes(array) % self.field.modulus as u128;
+
+        self.field.element(n as u64)
+    }
+
+    // The derived constants are compared too, as a proof could carry the right modulus with forged ones.
+    fn same_field(&self, other: &Self) -> bool {
+        self.field == other.field
+    }
+}
+
+// Implements the `Hashable` trait to make `MontgomeryElement` usable in a Merkle tree.
+// The canonical value is hashed, so the commitment does not depend on the representation.
+impl Hashable for MontgomeryElement {
+    fn update_context(&self, context: &mut Context) {
+        context.update(&self.value().to_le_bytes());
+    }
+}
+
+// Implements the `Add` trait; the carry out of 64 bits is handled for moduli above 2^63.
+impl Add for MontgomeryElement {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let modulus = self.field.modulus;
+        let (sum, carry) = self.value.overflowing_add(rhs.value);
+        let value = if carry || sum >= modulus {
+            sum.wrapping_sub(modulus)
+        } else {
+            sum
+        };
+
+        MontgomeryElement { value, ..self }
+    }
+}
+
+// Implements the `Sub` trait for subtraction of `MontgomeryElement` instances.
+impl Sub for MontgomeryElement {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let (difference, borrow) = self.value.overflowing_sub(rhs.value);
+        let value = if borrow {
+            difference.wrapping_add(self.field.modulus)
+        } else {
+            difference
+        };
+
+        MontgomeryElement { value, ..self }
+    }
+}
+
+// Implements the `Mul` trait: `(aR)(bR) / R = abR`, a single Montgomery reduction.
+impl Mul for MontgomeryElement {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        MontgomeryElement {
+            value: self.field.reduce(self.value as u128 * rhs.value as u128),
+            ..self
+        }
+    }
+}
+
+// Implements the `Div` trait for division of `MontgomeryElement` instances.
+impl Div for MontgomeryElement {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(rhs.value != 0, "divide by 0");
+
+        self * rhs.inverse()
+    }
+}
+
+// Implements the `Neg` trait for negation of `MontgomeryElement` instances.
+impl Neg for MontgomeryElement {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.zero() - self
+    }
+}
+
+// Fixtures shared by the tests of the fields checked against `MontgomeryField`.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::MontgomeryField;
+    use crate::{low_degree_extend, prove_low_degree, verify_fri};
+    use crate::{FriParams, FriProof, HashFunction, Polynomial, PrimeField};
+
+    // Pseudo-random values covering the whole `u64` range, including the extremes around `modulus`.
+    pub(crate) fn sample_values(modulus: u64) -> Vec<u64> {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut values = vec![
+            0,
+            1,
+            2,
+            modulus - 2,
+            modulus - 1,
+            modulus,
+            u32::MAX as u64,
+            u64::MAX,
+        ];
+        for _ in 0..500 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            values.push(state);
+        }
+
+        values
+    }
+
+    // Checks the arithmetic and the generator of the field of `one`, of prime `modulus`, against
+    // `MontgomeryField` on `sample_values`; `value` returns the canonical value of an element.
+    pub(crate) fn assert_matches_montgomery<F: PrimeField>(
+        one: F,
+        modulus: u64,
+        value: impl Fn(F) -> u64,
+    ) {
+        let field = MontgomeryField::new(modulus);
+        let values = sample_values(modulus);
+        for (&a, &b) in values.iter().zip(values.iter().rev()) {
+            let (x, y) = (one.integer(a), one.integer(b));
+            let (u, v) = (field.element(a), field.element(b));
+
+            assert_eq!(value(x), u.value());
+            assert_eq!(value(x + y), (u + v).value());
+            assert_eq!(value(x - y), (u - v).value());
+            assert_eq!(value(x * y), (u * v).value());
+            assert_eq!(value(-x), (-u).value());
+            if !y.is_zero() {
+                assert_eq!(value(x / y), (u / v).value());
+            }
+        }
+        assert_eq!(value(one.generator()), field.generator().value());
+    }
+
+    // Proves that a codeword of degree below 16 over the field of `one` is close to the code, checks that
+    // the proof verifies and that it no longer does once its final polynomial is changed.
+    // The proof is returned so that representations of the same field can be compared.
+    pub(crate) fn assert_fri_round_trip<F: PrimeField>(one: F) -> FriProof<F> {
+        let poly = Polynomial::new((1..17).map(|c| one.integer(c * 0x1234_5678_9ABC)).collect());
+        let (domain, codeword) = low_degree_extend(&poly, 4, one.generator()).unwrap();
+        let params = FriParams::new(16, 4, 2, 8, 0, 0, HashFunction::Sha256).unwrap();
+
+        let proof = prove_low_degree(codeword, &domain, &params).unwrap();
+        assert!(verify_fri(&proof, &domain, &params).is_ok());
+
+        let mut tampered = proof.clone();
+        tampered.final_polynomial.coeffs[0] = tampered.final_polynomial.coeffs[0] + one;
+        assert!(verify_fri(&tampered, &domain, &params).is_err());
+
+        proof
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::{assert_fri_round_trip, sample_values};
+    use super::*;
+
+    // Primes exercising every size class: small, just below 2^63, above 2^63 and just below 2^64.
+    const PRIMES: [u64; 5] = [
+        97,
+        998244353,
+        (1 << 63) - 25,
+        0xFFFF_FFFF_0000_0001,
+        u64::MAX - 58,
+    ];
+
+    #[test]
+    fn arithmetic_matches_u128_reference() {
+        for modulus in PRIMES {
+            let field = MontgomeryField::new(modulus);
+            let p = modulus as u128;
+            let values = sample_values(modulus);
+            for (&a, &b) in values.iter().zip(values.iter().rev()) {
+                let (x, y) = (field.element(a), field.element(b));
+                let (a, b) = (a as u128 % p, b as u128 % p);
+
+                assert_eq!(x.value() as u128, a);
+                assert_eq!((x + y).value() as u128, (a + b) % p);
+                assert_eq!((x - y).value() as u128, (a + p - b) % p);
+                assert_eq!((x * y).value() as u128, a * b % p);
+                assert_eq!((-x).value() as u128, (p - a) % p);
+                if b != 0 {
+                    assert_eq!((x / y) * y, x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn element_reduces_any_u64() {
+        let field = MontgomeryField::new(97);
+
+        assert_eq!(field.element(97), field.zero());
+        assert_eq!(field.element(u64::MAX).value(), u64::MAX % 97);
+        assert_eq!(field.element(1), field.one());
+    }
+
+    #[test]
+    fn inverse_and_generator() {
+        for modulus in PRIMES {
+            let field = MontgomeryField::new(modulus);
+            for a in sample_values(modulus)
+                .into_iter()
+                .filter(|&a| a % modulus != 0)
+            {
+                let x = field.element(a);
+                assert_eq!(x * x.inverse(), field.one());
+            }
+        }
+
+        assert_eq!(MontgomeryField::new(97).generator().value(), 5);
+        assert_eq!(
+            MontgomeryField::new(0xFFFF_FFFF_0000_0001)
+                .generator()
+                .value(),
+            7
+        );
+    }
+
+    #[test]
+    fn contains_rejects_other_fields_and_forged_constants() {
+        let field = MontgomeryField::new(97);
+        let x = field.element(5);
+        assert!(field.contains(&x));
+        assert!(!MontgomeryField::new(101).contains(&x));
+
+        let forged = MontgomeryField { r2: 1, ..field };
+        assert!(!forged.contains(&x));
+        assert!(!field.contains(&MontgomeryElement { value: 97, field }));
+    }
+
+    #[test]
+    fn fri_over_a_prime_above_2_63() {
+        assert_fri_round_trip(MontgomeryField::new(0xFFFF_FFFF_0000_0001).one());
+    }
+}
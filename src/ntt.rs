@@ -0,0 +1,161 @@
+use crate::{EvaluationDomain, PrimeField};
+
+/// The `Ntt` struct performs radix-2 number-theoretic transforms over a power-of-two subgroup.
+/// The twiddle factors (powers of the subgroup generator and of its inverse) are computed once
+/// when the struct is built, so it can be reused for every transform of that size: callers transforming
+/// many vectors over the same subgroup hold one and pass it to `Polynomial::evaluate_coset_with`
+/// and `Polynomial::interpolate_with`.
+#[derive(Debug, Clone)]
+pub struct Ntt<F> {
+    pub size: usize,          // Size of the transform, a power of two.
+    pub generator: F,         // Generator of the subgroup the transform evaluates over.
+    twiddles: Vec<F>,         // generator^k for k in 0..size/2.
+    inverse_twiddles: Vec<F>, // generator^-k for k in 0..size/2.
+    size_inverse: F,          // 1 / size, to scale the inverse transform.
+}
+
+impl<F: PrimeField> Ntt<F> {
+    // Precomputes the twiddle factors for the subgroup of `domain`. The coset offset is ignored,
+    // callers shift the coefficients themselves to work on a coset.
+    pub fn new(domain: &EvaluationDomain<F>) -> Self {
+        let size = domain.size;
+        assert!(
+            size.is_power_of_two(),
+            "NTT size must be a power of two, got {}",
+            size
+        );
+
+        let generator = domain.generator;
+        let generator_inverse = generator.inverse();
+
+        let mut twiddles = Vec::with_capacity(size / 2);
+        let mut inverse_twiddles = Vec::with_capacity(size / 2);
+        let (mut w, mut w_inv) = (generator.one(), generator.one());
+        for _ in 0..size / 2 {
+            twiddles.push(w);
+            inverse_twiddles.push(w_inv);
+            w = w * generator;
+            w_inv = w_inv * generator_inverse;
+        }
+
+        Self {
+            size,
+            generator,
+            twiddles,
+            inverse_twiddles,
+            size_inverse: generator.integer(size as u64).inverse(),
+        }
+    }
+
+    // Returns whether the transform evaluates over the subgroup of `domain`, whatever its offset.
+    pub fn matches(&self, domain: &EvaluationDomain<F>) -> bool {
+        self.size == domain.size && self.generator == domain.generator
+    }
+
+    // Replaces the coefficients `values` by their evaluations at `generator^i`, in natural order.
+    pub fn forward(&self, values: &mut [F]) {
+        self.transform(values, &self.twiddles);
+    }
+
+    // Replaces the evaluations `values` at `generator^i` by the coefficients they interpolate.
+    pub fn inverse(&self, values: &mut [F]) {
+        self.transform(values, &self.inverse_twiddles);
+        for value in values.iter_mut() {
+            *value = *value * self.size_inverse;
+        }
+    }
+
+    // Iterative Cooley-Tukey transform: bit-reversal permutation followed by log2(size)
+    // stages of butterflies, each stage doubling the size of the sub-transforms.
+    fn transform(&self, values: &mut [F], twiddles: &[F]) {
+        let n = self.size;
+        assert_eq!(values.len(), n, "NTT input must have {} values", n);
+
+        bit_reverse_permute(values);
+
+        let mut half = 1;
+        while half < n {
+            let step = n / (2 * half);
+            for start in (0..n).step_by(2 * half) {
+                for j in 0..half {
+                    let u = values[start + j];
+                    let v = values[start + j + half] * twiddles[j * step];
+                    values[start + j] = u + v;
+                    values[start + j + half] = u - v;
+                }
+            }
+            half *= 2;
+        }
+    }
+}
+
+// Reorders `values` so that the element at index `i` moves to the bit-reversal of `i`.
+fn bit_reverse_permute<T>(values: &mut [T]) {
+    let n = values.len();
+    if n <= 2 {
+        return;
+    }
+
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Field, FieldElement, Polynomial};
+
+    #[test]
+    fn ntt_matches_direct_evaluation() {
+        let field = Field::new(998244353);
+        let domain = EvaluationDomain::from_size(16, field.one()).unwrap();
+        let coeffs: Vec<FieldElement> = (0..16)
+            .map(|i| FieldElement::new(i * i + 7, field))
+            .collect();
+
+        let mut values = coeffs.clone();
+        Ntt::new(&domain).forward(&mut values);
+
+        let expected = Polynomial::new(coeffs).evaluate_domain(&domain.elements());
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn ntt_inverse_round_trips() {
+        let field = Field::new(97);
+        let ntt = Ntt::new(&EvaluationDomain::from_size(32, field.one()).unwrap());
+        let coeffs: Vec<FieldElement> = (0..32).map(|i| FieldElement::new(3 * i, field)).collect();
+
+        let mut values = coeffs.clone();
+        ntt.forward(&mut values);
+        ntt.inverse(&mut values);
+
+        assert_eq!(values, coeffs);
+    }
+
+    #[test]
+    fn ntt_is_reused_across_cosets_of_its_subgroup() {
+        let field = Field::new(97);
+        let ntt = Ntt::new(&EvaluationDomain::from_size(16, field.one()).unwrap());
+        let poly = Polynomial::new(
+            (0..16)
+                .map(|i| FieldElement::new(5 * i + 2, field))
+                .collect(),
+        );
+
+        for offset in [field.one(), field.generator(), FieldElement::new(10, field)] {
+            let domain = EvaluationDomain::from_size(16, offset).unwrap();
+            assert!(ntt.matches(&domain));
+
+            let values = poly.evaluate_coset_with(&ntt, &domain);
+            assert_eq!(values, poly.evaluate_coset(&domain));
+            assert_eq!(Polynomial::interpolate_with(&ntt, &domain, &values), poly);
+        }
+        assert!(!ntt.matches(&EvaluationDomain::from_size(32, field.one()).unwrap()));
+    }
+}
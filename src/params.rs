@@ -0,0 +1,409 @@
+use crate::FriError;
+use ring::digest::{Algorithm, SHA256, SHA384, SHA512};
+use serde::{Deserialize, Serialize};
+
+// Grinding beyond this many bits would take the prover far too long to be a sensible setting.
+const MAX_GRINDING_BITS: u32 = 32;
+
+/// The `HashFunction` enum selects the hash used to build the Merkle commitments of the FRI layers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HashFunction {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl HashFunction {
+    // Returns the `ring` digest algorithm implementing this hash function.
+    pub fn algorithm(&self) -> &'static Algorithm {
+        match self {
+            HashFunction::Sha256 => &SHA256,
+            HashFunction::Sha384 => &SHA384,
+            HashFunction::Sha512 => &SHA512,
+        }
+    }
+
+    // Returns the size of a digest in bits.
+    pub fn output_bits(&self) -> usize {
+        self.algorithm().output_len * 8
+    }
+}
+
+/// The `FriParams` struct gathers the public parameters shared by the prover and the verifier.
+/// They are validated together by `FriParams::new`, which also derives the number of layers,
+/// so a proof is always produced and checked against a consistent configuration. The fields are
+/// private and deserialization goes through `new` too, so no unvalidated parameters can be built.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawFriParams", into = "RawFriParams")]
+pub struct FriParams {
+    degree_bound: usize, // Strict upper bound on the degree of the committed polynomial.
+    blowup: usize,       // Inverse of the code rate, the domain has `degree_bound * blowup` points.
+    folding_factor: usize, // Number of evaluations folded together in each round.
+    number_of_queries: usize, // Number of positions of the first layer opened by the prover.
+    final_poly_max_degree: usize, // Maximum degree of the polynomial sent in the clear after the last layer.
+    grinding_bits: u32, // Leading zero bits of the proof-of-work required before the queries.
+    hash: HashFunction, // Hash function of the Merkle commitments.
+    number_layers: usize, // Number of committed layers, derived from the other parameters.
+    deep_sampling: bool, // Whether FRI runs on the DEEP quotient at an out-of-domain point.
+}
+
+/// The `RawFriParams` struct is the serialized form of `FriParams`, without the derived number of layers.
+/// It is only converted back through `FriParams::new`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct RawFriParams {
+    degree_bound: usize,
+    blowup: usize,
+    folding_factor: usize,
+    number_of_queries: usize,
+    final_poly_max_degree: usize,
+    grinding_bits: u32,
+    hash: HashFunction,
+    deep_sampling: bool,
+}
+
+impl TryFrom<RawFriParams> for FriParams {
+    type Error = FriError;
+
+    fn try_from(raw: RawFriParams) -> Result<Self, Self::Error> {
+        let params = FriParams::new(
+            raw.degree_bound,
+            raw.blowup,
+            raw.folding_factor,
+            raw.number_of_queries,
+            raw.final_poly_max_degree,
+            raw.grinding_bits,
+            raw.hash,
+        )?;
+
+        Ok(Self {
+            deep_sampling: raw.deep_sampling,
+            ..params
+        })
+    }
+}
+
+impl From<FriParams> for RawFriParams {
+    fn from(params: FriParams) -> Self {
+        Self {
+            degree_bound: params.degree_bound,
+            blowup: params.blowup,
+            folding_factor: params.folding_factor,
+            number_of_queries: params.number_of_queries,
+            final_poly_max_degree: params.final_poly_max_degree,
+            grinding_bits: params.grinding_bits,
+            hash: params.hash,
+            deep_sampling: params.deep_sampling,
+        }
+    }
+}
+
+impl FriParams {
+    // Validates a FRI configuration and derives its number of layers.
+    // Each layer divides the degree bound by the folding factor, and folding stops as soon as the
+    // remaining polynomial has degree at most `final_poly_max_degree`. At least one layer is committed.
+    pub fn new(
+        degree_bound: usize,
+        blowup: usize,
+        folding_factor: usize,
+        number_of_queries: usize,
+        final_poly_max_degree: usize,
+        grinding_bits: u32,
+        hash: HashFunction,
+    ) -> Result<Self, FriError> {
+        if blowup < 2 || !blowup.is_power_of_two() {
+            return Err(FriError::InvalidBlowup { blowup });
+        }
+        if folding_factor < 2 || !folding_factor.is_power_of_two() {
+            return Err(FriError::InvalidFoldingFactor { folding_factor });
+        }
+        let domain_size = degree_bound
+            .checked_mul(blowup)
+            .ok_or(FriError::InvalidBlowup { blowup })?;
+
+        // Each fold must divide the degree bound exactly, so it is a power of two allowing at least one fold.
+        if degree_bound < folding_factor || !degree_bound.is_power_of_two() {
+            return Err(FriError::InvalidDegreeBound {
+                degree_bound,
+                domain_size,
+            });
+        }
+
+        // Fold while the degree bound is above the final one and can still be divided by the folding factor.
+        let final_bound = final_poly_max_degree.saturating_add(1);
+        let mut number_layers = 1;
+        let mut final_degree_bound = degree_bound / folding_factor;
+        while final_degree_bound > final_bound && final_degree_bound >= folding_factor {
+            final_degree_bound /= folding_factor;
+            number_layers += 1;
+        }
+        if final_degree_bound > final_bound {
+            return Err(FriError::InvalidFinalDegree {
+                final_poly_max_degree,
+                degree_bound,
+                folding_factor,
+            });
+        }
+        if number_of_queries == 0 || number_of_queries > domain_size {
+            return Err(FriError::InvalidNumberOfQueries {
+                number_of_queries,
+                domain_size,
+            });
+        }
+        if grinding_bits > MAX_GRINDING_BITS {
+            return Err(FriError::InvalidGrindingBits {
+                grinding_bits,
+                max: MAX_GRINDING_BITS,
+            });
+        }
+
+        Ok(Self {
+            degree_bound,
+            blowup,
+            folding_factor,
+            number_of_queries,
+            final_poly_max_degree,
+            grinding_bits,
+            hash,
+            number_layers,
+            deep_sampling: false,
+        })
+    }
+
+    // Returns the same parameters with DEEP sampling enabled: the codeword is committed as is, then FRI
+    // runs on its quotient `(f(x) - f(z)) / (x - z)` at a point `z` outside the domain drawn from the transcript.
+    // Tying the codeword to its value at a random point gives better soundness per query.
+    pub fn with_deep_sampling(self) -> Self {
+        Self {
+            deep_sampling: true,
+            ..self
+        }
+    }
+
+    // Returns the same parameters with `number_of_queries` queries, validated as by `new`.
+    pub fn with_number_of_queries(self, number_of_queries: usize) -> Result<Self, FriError> {
+        if number_of_queries == 0 || number_of_queries > self.domain_size() {
+            return Err(FriError::InvalidNumberOfQueries {
+                number_of_queries,
+                domain_size: self.domain_size(),
+            });
+        }
+
+        Ok(Self {
+            number_of_queries,
+            ..self
+        })
+    }
+
+    // Returns the parameters of FRI on the codeword after the first fold, for protocols that fold the first
+    // layer themselves: the degree bound and the domain are divided by the folding factor, the rest is kept.
+    // Fails with `SingleLayer` when the parameters commit one layer, as its fold is already the final polynomial.
+    pub fn after_first_fold(&self) -> Result<Self, FriError> {
+        if self.number_layers == 1 {
+            return Err(FriError::SingleLayer {
+                degree_bound: self.degree_bound,
+                folding_factor: self.folding_factor,
+            });
+        }
+        let folded = FriParams::new(
+            self.degree_bound / self.folding_factor,
+            self.blowup,
+            self.folding_factor,
+            self.number_of_queries,
+            self.final_poly_max_degree,
+            self.grinding_bits,
+            self.hash,
+        )?;
+
+        Ok(Self {
+            deep_sampling: self.deep_sampling,
+            ..folded
+        })
+    }
+
+    // Returns the strict upper bound on the degree of the committed polynomial.
+    pub fn degree_bound(&self) -> usize {
+        self.degree_bound
+    }
+
+    // Returns the blowup factor, the inverse of the code rate.
+    pub fn blowup(&self) -> usize {
+        self.blowup
+    }
+
+    // Returns the number of evaluations folded together in each round.
+    pub fn folding_factor(&self) -> usize {
+        self.folding_factor
+    }
+
+    // Returns the number of positions of the first layer opened by the prover.
+    pub fn number_of_queries(&self) -> usize {
+        self.number_of_queries
+    }
+
+    // Returns the maximum degree of the polynomial sent in the clear after the last layer.
+    pub fn final_poly_max_degree(&self) -> usize {
+        self.final_poly_max_degree
+    }
+
+    // Returns the number of leading zero bits required of the proof-of-work.
+    pub fn grinding_bits(&self) -> u32 {
+        self.grinding_bits
+    }
+
+    // Returns the hash function of the Merkle commitments.
+    pub fn hash(&self) -> HashFunction {
+        self.hash
+    }
+
+    // Returns the number of committed layers.
+    pub fn number_layers(&self) -> usize {
+        self.number_layers
+    }
+
+    // Returns true when FRI runs on the DEEP quotient at an out-of-domain point.
+    pub fn deep_sampling(&self) -> bool {
+        self.deep_sampling
+    }
+
+    // Returns the size of the first evaluation domain, `degree_bound * blowup`.
+    pub fn domain_size(&self) -> usize {
+        self.degree_bound * self.blowup
+    }
+
+    // Returns the number of coefficients of the final polynomial, the degree bound after all the folds.
+    pub fn final_degree_bound(&self) -> usize {
+        self.degree_bound / self.folding_factor.pow(self.number_layers as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fri_params_derive_the_number_of_layers() {
+        let params = FriParams::new(8, 4, 2, 10, 0, 0, HashFunction::Sha256).unwrap();
+        assert_eq!(params.number_layers(), 3);
+        assert_eq!(params.domain_size(), 32);
+        let folded = params.after_first_fold().unwrap();
+        assert_eq!(folded.number_layers(), 2);
+        assert_eq!(folded.domain_size(), 16);
+        assert_eq!(folded.final_degree_bound(), params.final_degree_bound());
+
+        let params = FriParams::new(1 << 12, 8, 16, 30, 0, 16, HashFunction::Sha512).unwrap();
+        assert_eq!(params.number_layers(), 3);
+        assert_eq!(params.domain_size(), 1 << 15);
+        assert_eq!(params.hash().output_bits(), 512);
+        assert!(!params.deep_sampling());
+        assert!(params.with_deep_sampling().deep_sampling());
+        assert!(params
+            .with_deep_sampling()
+            .after_first_fold()
+            .unwrap()
+            .deep_sampling());
+    }
+
+    #[test]
+    fn fri_params_stop_early_with_a_final_polynomial() {
+        let sha = HashFunction::Sha256;
+        let params = FriParams::new(1 << 10, 4, 2, 10, 31, 0, sha).unwrap();
+        assert_eq!(params.number_layers(), 5);
+        assert_eq!(params.final_degree_bound(), 32);
+
+        // folding 8 by 4 leaves a degree bound of 2, too much for a constant but fine for a line
+        let params = FriParams::new(8, 4, 4, 10, 1, 0, sha).unwrap();
+        assert_eq!(params.number_layers(), 1);
+        assert_eq!(params.final_degree_bound(), 2);
+        assert_eq!(
+            params.after_first_fold().unwrap_err(),
+            FriError::SingleLayer {
+                degree_bound: 8,
+                folding_factor: 4
+            }
+        );
+
+        // a generous final degree still commits one layer
+        let params = FriParams::new(8, 4, 2, 10, 100, 0, sha).unwrap();
+        assert_eq!(params.number_layers(), 1);
+        assert_eq!(params.final_degree_bound(), 4);
+    }
+
+    #[test]
+    fn fri_params_reject_inconsistent_combinations() {
+        let sha = HashFunction::Sha256;
+        assert_eq!(
+            FriParams::new(8, 3, 2, 10, 0, 0, sha).unwrap_err(),
+            FriError::InvalidBlowup { blowup: 3 }
+        );
+        assert_eq!(
+            FriParams::new(8, 4, 6, 10, 0, 0, sha).unwrap_err(),
+            FriError::InvalidFoldingFactor { folding_factor: 6 }
+        );
+        for degree_bound in [0, 1, 2, 12] {
+            assert_eq!(
+                FriParams::new(degree_bound, 4, 4, 10, 0, 0, sha).unwrap_err(),
+                FriError::InvalidDegreeBound {
+                    degree_bound,
+                    domain_size: degree_bound * 4
+                }
+            );
+        }
+        // 8 and 32 cannot be folded by 4 down to a constant
+        for degree_bound in [8, 32] {
+            assert_eq!(
+                FriParams::new(degree_bound, 4, 4, 10, 0, 0, sha).unwrap_err(),
+                FriError::InvalidFinalDegree {
+                    final_poly_max_degree: 0,
+                    degree_bound,
+                    folding_factor: 4
+                }
+            );
+        }
+        for number_of_queries in [0, 33] {
+            assert_eq!(
+                FriParams::new(8, 4, 2, number_of_queries, 0, 0, sha).unwrap_err(),
+                FriError::InvalidNumberOfQueries {
+                    number_of_queries,
+                    domain_size: 32
+                }
+            );
+        }
+        assert_eq!(
+            FriParams::new(8, 4, 2, 10, 0, 40, sha).unwrap_err(),
+            FriError::InvalidGrindingBits {
+                grinding_bits: 40,
+                max: 32
+            }
+        );
+    }
+
+    #[test]
+    fn fri_params_are_only_built_through_new() {
+        let params = FriParams::new(8, 4, 2, 10, 0, 0, HashFunction::Sha256).unwrap();
+        assert_eq!(
+            params.with_number_of_queries(0).unwrap_err(),
+            FriError::InvalidNumberOfQueries {
+                number_of_queries: 0,
+                domain_size: 32
+            }
+        );
+        assert_eq!(
+            params
+                .with_number_of_queries(32)
+                .unwrap()
+                .number_of_queries(),
+            32
+        );
+
+        // the derived number of layers is not serialized, and deserialization validates the rest
+        let deep = params.with_deep_sampling();
+        let json = serde_json::to_string(&deep).unwrap();
+        assert!(!json.contains("number_layers"));
+        assert_eq!(serde_json::from_str::<FriParams>(&json).unwrap(), deep);
+
+        let forged = json.replace("\"number_of_queries\":10", "\"number_of_queries\":0");
+        assert!(serde_json::from_str::<FriParams>(&forged).is_err());
+        let forged = json.replace("\"folding_factor\":2", "\"folding_factor\":3");
+        assert!(serde_json::from_str::<FriParams>(&forged).is_err());
+    }
+}
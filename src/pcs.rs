@@ -0,0 +1,355 @@
+use crate::VerificationError;
+use crate::{check_reduced, verify_quotient_openings, CommitmentOpening, Quotient};
+use crate::{open_codeword, prove_fri_with_transcript, verify_fri_with_transcript};
+use crate::{proof_from_bytes, proof_to_bytes, FriLayer, FriProof, ProofStream};
+use crate::{EvaluationDomain, FriError, FriParams, Polynomial, PrimeField};
+use serde::{Deserialize, Serialize};
+
+// Transcript labels of the opening, absorbed before the FRI commit phase on the quotient.
+const COMMITMENT_LABEL: &str = "pcs_commitment";
+const EVALUATIONS_LABEL: &str = "pcs_evaluations";
+const QUOTIENT_GAMMA_LABEL: &str = "pcs_quotient_gamma";
+
+/// The `PolynomialCommitment` struct is what the verifier keeps of a committed polynomial:
+/// the Merkle root of its codeword over the evaluation domain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolynomialCommitment {
+    pub root: Vec<u8>,
+}
+
+/// The `CommittedPolynomial` struct holds the prover-side data of a commitment, needed to open it later.
+/// The codeword is committed like a `FriLayer`, so its leaves line up with the cosets FRI opens.
+#[derive(Debug, Clone)]
+pub struct CommittedPolynomial<F> {
+    pub polynomial: Polynomial<F>, // The committed polynomial, of degree below `params.degree_bound()`.
+    pub layer: FriLayer<F>, // Its codeword over the evaluation domain and the Merkle tree on it.
+}
+
+/// The `EvaluationProof` struct proves that a committed polynomial takes the claimed values at some points.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationProof<F> {
+    pub openings: Vec<CommitmentOpening<F>>, // One opening of the committed codeword per query.
+    pub fri_proof: FriProof<F>,              // Proof of proximity of the combined quotient.
+}
+
+impl<F: PrimeField> EvaluationProof<F> {
+    // Serializes the evaluation proof with `proof_to_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        proof_to_bytes(self)
+    }
+
+    // Reconstructs the evaluation proof from the bytes produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, serde_json::Error> {
+        proof_from_bytes(bytes)
+    }
+}
+
+// Commits to `polynomial`, of degree below `params.degree_bound()`, by evaluating it over `domain`
+// and building a Merkle tree on the codeword.
+// Returns the commitment for the verifier and the data the prover needs to open it.
+pub fn commit<F: PrimeField>(
+    polynomial: &Polynomial<F>,   // Polynomial to commit to.
+    domain: &EvaluationDomain<F>, // Evaluation domain, of size `params.domain_size()`.
+    params: &FriParams,           // Parameters of the FRI instances run by the openings.
+) -> Result<(PolynomialCommitment, CommittedPolynomial<F>), FriError> {
+    if domain.size != params.domain_size() {
+        return Err(FriError::DomainSizeMismatch {
+            expected: params.domain_size(),
+            found: domain.size,
+        });
+    }
+    if let Some(degree) = polynomial.degree().filter(|&d| d >= params.degree_bound()) {
+        return Err(FriError::PolynomialDegreeTooLarge {
+            degree,
+            degree_bound: params.degree_bound(),
+        });
+    }
+
+    let mut polynomial = polynomial.clone();
+    polynomial.normalize();
+    let layer = FriLayer::new(&polynomial, *domain, params);
+    let commitment = PolynomialCommitment {
+        root: layer.merkle_tree.root_hash().clone(),
+    };
+
+    Ok((commitment, CommittedPolynomial { polynomial, layer }))
+}
+
+// Opens a committed polynomial `p` at the points `z_i`, which must lie outside the evaluation domain.
+// Returns the values `y_i = p(z_i)` and a proof that they are correct: FRI on the combination of the quotients
+//
+//     q_i(x) = (p(x) - y_i) / (x - z_i)
+//
+// which are polynomials of degree below `D - 1` exactly when `p(z_i) = y_i`. Each quotient is lifted by
+// `degree_adjusted_combination` to the degree bound `D = params.degree_bound()`, so an overly large `p` is caught too.
+pub fn open<F: PrimeField>(
+    committed: &CommittedPolynomial<F>, // Prover-side data returned by `commit`.
+    points: &[F],                       // Points to open the polynomial at.
+    params: &FriParams,                 // Parameters of the FRI instance on the quotient.
+) -> Result<(Vec<F>, EvaluationProof<F>), FriError> {
+    let domain = &committed.layer.domain;
+    if domain.size != params.domain_size() {
+        return Err(FriError::DomainSizeMismatch {
+            expected: params.domain_size(),
+            found: domain.size,
+        });
+    }
+    check_points(points, domain)?;
+    let values: Vec<F> = points
+        .iter()
+        .map(|z| committed.polynomial.evaluate(*z))
+        .collect();
+
+    let mut transcript = ProofStream::new();
+    let root = committed.layer.merkle_tree.root_hash();
+    let gamma = absorb_opening(&mut transcript, root, points, &values, &domain.generator);
+    let quotient = Quotient {
+        points: points.to_vec(),
+        values: values.clone(),
+        gamma,
+        degree_bound: params.degree_bound(),
+    };
+
+    let codeword = quotient.codeword(&committed.layer.evaluations, domain);
+    let fri_proof = prove_fri_with_transcript(codeword, domain, params, &mut transcript);
+
+    // open the committed codeword at the cosets opened in the first FRI layer
+    let proof = EvaluationProof {
+        openings: open_codeword(&committed.layer, &fri_proof.decommitments),
+        fri_proof,
+    };
+
+    Ok((values, proof))
+}
+
+// Verifies that the polynomial behind `commitment` takes the values `values[i]` at `points[i]`.
+// Beyond the FRI checks on the quotient, every query opens the committed codeword and recomputes the
+// combined quotient from it, which must give the values FRI opened in that quotient: its first layer,
+// or with DEEP sampling the codeword committed before taking the DEEP quotient.
+pub fn verify<F: PrimeField>(
+    commitment: &PolynomialCommitment, // Commitment returned by `commit`.
+    points: &[F],                      // Points the polynomial was opened at.
+    values: &[F],                      // Claimed values of the polynomial at these points.
+    proof: &EvaluationProof<F>,        // Proof returned by `open`.
+    domain: &EvaluationDomain<F>,      // Evaluation domain of the commitment.
+    params: &FriParams,                // Parameters of the FRI instance on the quotient.
+) -> Result<(), VerificationError> {
+    check_points(points, domain)?;
+    let field = domain.generator;
+    if values.len() != points.len() {
+        return Err(VerificationError::EvaluationCountMismatch {
+            expected: points.len(),
+            found: values.len(),
+        });
+    }
+    check_reduced(values, &field)?;
+
+    // replay the opening, then the FRI instance on the same transcript
+    let mut transcript = ProofStream::new();
+    let gamma = absorb_opening(&mut transcript, &commitment.root, points, values, &field);
+
+    let query_indices =
+        verify_fri_with_transcript(&proof.fri_proof, domain, params, &mut transcript)?;
+    let quotient = Quotient {
+        points: points.to_vec(),
+        values: values.to_vec(),
+        gamma,
+        degree_bound: params.degree_bound(),
+    };
+
+    verify_quotient_openings(
+        &commitment.root,
+        &proof.openings,
+        &proof.fri_proof.committed_cosets(),
+        &query_indices,
+        &quotient,
+        domain,
+        params,
+    )
+}
+
+// Checks that there is at least one opening point, and that every point is a reduced element of the
+// domain's field outside the domain, where the quotient would divide by zero.
+fn check_points<F: PrimeField>(points: &[F], domain: &EvaluationDomain<F>) -> Result<(), FriError> {
+    if points.is_empty() {
+        return Err(FriError::NoOpeningPoints);
+    }
+    for (index, z) in points.iter().enumerate() {
+        if !z.is_reduced_in(&domain.generator) {
+            return Err(FriError::FieldMismatch);
+        }
+        if domain.contains(z) {
+            return Err(FriError::OpeningPointInDomain { index });
+        }
+    }
+
+    Ok(())
+}
+
+// Absorbs the commitment and the claimed evaluations, then squeezes the challenge combining the quotients.
+fn absorb_opening<F: PrimeField>(
+    transcript: &mut ProofStream,
+    root: &[u8],
+    points: &[F],
+    values: &[F],
+    field: &F,
+) -> F {
+    transcript.absorb(COMMITMENT_LABEL, root);
+    let bytes: Vec<u8> = points
+        .iter()
+        .zip(values.iter())
+        .flat_map(|(z, y)| [z.to_bytes(), y.to_bytes()].concat())
+        .collect();
+    transcript.absorb(EVALUATIONS_LABEL, &bytes);
+
+    transcript.squeeze_field_element(QUOTIENT_GAMMA_LABEL, field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Field, FieldElement, HashFunction};
+
+    fn setup() -> (
+        Polynomial<FieldElement>,
+        EvaluationDomain<FieldElement>,
+        FriParams,
+    ) {
+        let field = Field::new(97);
+        let coeffs = [19, 56, 34, 48, 43, 37, 10, 5];
+        let poly = Polynomial::new(
+            coeffs
+                .iter()
+                .map(|&c| FieldElement::new(c, field))
+                .collect(),
+        );
+        let domain = EvaluationDomain::from_size(32, field.generator()).unwrap();
+        let params = FriParams::new(8, 4, 2, 8, 0, 0, HashFunction::Sha256).unwrap();
+
+        (poly, domain, params)
+    }
+
+    // Returns points of the field outside the domain.
+    fn outside_points(domain: &EvaluationDomain<FieldElement>, count: usize) -> Vec<FieldElement> {
+        let elements = domain.elements();
+        (0..97)
+            .map(|n| FieldElement::new(n, domain.field()))
+            .filter(|z| !elements.contains(z))
+            .step_by(7)
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn open_proves_evaluations_at_one_and_several_points() {
+        let (poly, domain, params) = setup();
+        let (commitment, committed) = commit(&poly, &domain, &params).unwrap();
+
+        for count in [1, 3] {
+            let points = outside_points(&domain, count);
+            let (values, proof) = open(&committed, &points, &params).unwrap();
+            let received = EvaluationProof::from_bytes(&proof.to_bytes()).unwrap();
+
+            let expected: Vec<FieldElement> = points.iter().map(|z| poly.evaluate(*z)).collect();
+            assert_eq!(values, expected);
+            assert!(verify(&commitment, &points, &values, &received, &domain, &params).is_ok());
+        }
+    }
+
+    #[test]
+    fn open_verifies_with_deep_sampling() {
+        let (poly, domain, _) = setup();
+        let params = FriParams::new(8, 4, 2, 8, 0, 0, HashFunction::Sha256)
+            .unwrap()
+            .with_deep_sampling();
+        let (commitment, committed) = commit(&poly, &domain, &params).unwrap();
+        let points = outside_points(&domain, 2);
+
+        let (values, proof) = open(&committed, &points, &params).unwrap();
+        let received = EvaluationProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(received.fri_proof.deep.is_some());
+        assert!(verify(&commitment, &points, &values, &received, &domain, &params).is_ok());
+
+        let mut wrong_values = values;
+        wrong_values[0] = wrong_values[0] + domain.field().one();
+        assert!(verify(
+            &commitment,
+            &points,
+            &wrong_values,
+            &proof,
+            &domain,
+            &params
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn verify_rejects_wrong_values_and_commitments() {
+        let (poly, domain, params) = setup();
+        let (commitment, committed) = commit(&poly, &domain, &params).unwrap();
+        let points = outside_points(&domain, 2);
+        let (values, proof) = open(&committed, &points, &params).unwrap();
+
+        let mut wrong_values = values.clone();
+        wrong_values[1] = wrong_values[1] + domain.field().one();
+        assert!(verify(
+            &commitment,
+            &points,
+            &wrong_values,
+            &proof,
+            &domain,
+            &params
+        )
+        .is_err());
+
+        let other = Polynomial::new(vec![domain.field().one(); 8]);
+        let (other_commitment, _) = commit(&other, &domain, &params).unwrap();
+        assert!(verify(
+            &other_commitment,
+            &points,
+            &values,
+            &proof,
+            &domain,
+            &params
+        )
+        .is_err());
+
+        let mut foreign = proof.clone();
+        foreign.openings[0].evaluations[1].field = Field::new(193);
+        assert_eq!(
+            verify(&commitment, &points, &values, &foreign, &domain, &params),
+            Err(VerificationError::NonReducedElement)
+        );
+
+        let mut tampered = proof;
+        tampered.openings[0].evaluations[0] =
+            tampered.openings[0].evaluations[0] + domain.field().one();
+        assert!(verify(&commitment, &points, &values, &tampered, &domain, &params).is_err());
+    }
+
+    #[test]
+    fn commit_and_open_validate_their_inputs() {
+        let (poly, domain, params) = setup();
+        let field = domain.field();
+
+        let large = Polynomial::new(vec![field.one(); 9]);
+        assert_eq!(
+            commit(&large, &domain, &params).unwrap_err(),
+            FriError::PolynomialDegreeTooLarge {
+                degree: 8,
+                degree_bound: 8
+            }
+        );
+
+        let (_, committed) = commit(&poly, &domain, &params).unwrap();
+        assert_eq!(
+            open(&committed, &[], &params).unwrap_err(),
+            FriError::NoOpeningPoints
+        );
+        let inside = domain.element(5);
+        assert_eq!(
+            open(&committed, &[inside], &params).unwrap_err(),
+            FriError::OpeningPointInDomain { index: 0 }
+        );
+    }
+}
@@ -1,36 +1,189 @@
-use crate::FieldElement;
+use crate::{EvaluationDomain, FriError, Ntt, PrimeField};
 use serde::{Deserialize, Serialize};
+use std::ops::{Add, Mul, Neg, Sub};
+
+// Below this many coefficients in the smaller operand, schoolbook multiplication beats the NTT.
+const NTT_MULTIPLICATION_THRESHOLD: usize = 64;
 
 // The `Polynomial` struct represents a polynomial where the coefficients
-// are elements in a finite field (FieldElement).
+// are elements of a prime field `F`.
 #[derive(Clone, Serialize, Deserialize, Debug)]
-pub struct Polynomial {
-    pub coeffs: Vec<FieldElement>,
+pub struct Polynomial<F> {
+    pub coeffs: Vec<F>,
 }
 
-impl Polynomial {
+impl<F: PrimeField> Polynomial<F> {
     // Constructs a new polynomial from a vector of coefficients.
-    pub fn new(coeffs: Vec<FieldElement>) -> Self {
+    pub fn new(coeffs: Vec<F>) -> Self {
         Self { coeffs }
     }
 
-    // Evaluates the polynomial at a given point `x` (which is a `FieldElement`).
-    // This function implements Horner's method, evaluating the polynomial by
-    // iterating through the coefficients and computing the result.
-    pub fn evaluate(&self, x: FieldElement) -> FieldElement {
-        let mut xi = x.field.one();
-        let mut value = x.field.zero();
-        for c in self.coeffs.clone() {
-            value = value + c * xi;
-            xi = xi * x;
+    // Interpolates the polynomial of degree below `domain.size` that takes the given `values`
+    // at the points of `domain`, in index order.
+    // Power-of-two domains use the inverse NTT: it recovers the coefficients of `p(offset * x)`,
+    // which are then divided by the matching powers of the offset. Other domain sizes fall back
+    // to interpolating the domain points with `interpolate_points`.
+    pub fn interpolate(domain: &EvaluationDomain<F>, values: &[F]) -> Self {
+        assert_eq!(
+            values.len(),
+            domain.size,
+            "number of values and domain size differ"
+        );
+        if !domain.size.is_power_of_two() {
+            return Self::interpolate_points(&domain.elements(), values);
+        }
+
+        Self::interpolate_with(&Ntt::new(domain), domain, values)
+    }
+
+    // Interpolates like `interpolate` over a power-of-two domain, with the transform of its subgroup
+    // built by the caller, so that repeated interpolations of that size share the twiddle factors.
+    pub fn interpolate_with(ntt: &Ntt<F>, domain: &EvaluationDomain<F>, values: &[F]) -> Self {
+        assert!(ntt.matches(domain), "the NTT does not match the domain");
+        let mut coeffs = values.to_vec();
+        ntt.inverse(&mut coeffs);
+
+        let offset_inverse = domain.offset.inverse();
+        let mut offset_power = domain.offset.one();
+        for c in coeffs.iter_mut() {
+            *c = *c * offset_power;
+            offset_power = offset_power * offset_inverse;
+        }
+
+        Self::new(coeffs)
+    }
+
+    // Interpolates the polynomial of degree below `xs.len()` passing through the points `(xs[i], ys[i])`.
+    // The points must be distinct. Uses Newton's divided differences, then expands the Newton form
+    // `c_0 + (x - x_0)(c_1 + (x - x_1)(c_2 + ...))` into coefficients, in O(n^2) field operations.
+    pub fn interpolate_points(xs: &[F], ys: &[F]) -> Self {
+        assert_eq!(xs.len(), ys.len(), "number of points and values differ");
+        let n = xs.len();
+        if n == 0 {
+            return Self::new(vec![]);
+        }
+
+        // divided differences, computed in place: after step j, c[i] = f[x_{i-j}, ..., x_i] for i >= j
+        let mut c = ys.to_vec();
+        for j in 1..n {
+            for i in (j..n).rev() {
+                let denominator = xs[i] - xs[i - j];
+                assert!(
+                    !denominator.is_zero(),
+                    "interpolation points must be distinct"
+                );
+                c[i] = (c[i] - c[i - 1]) / denominator;
+            }
+        }
+
+        // expand the Newton form from the innermost term outwards
+        let mut coeffs = vec![xs[0].zero(); n];
+        coeffs[0] = c[n - 1];
+        for i in (0..n - 1).rev() {
+            // multiply the current polynomial of degree n - 2 - i by (x - x_i), then add c_i
+            for k in (1..n - i).rev() {
+                coeffs[k] = coeffs[k - 1] - coeffs[k] * xs[i];
+            }
+            coeffs[0] = c[i] - coeffs[0] * xs[i];
+        }
+
+        Self::new(coeffs)
+    }
+
+    // Returns the degree of the polynomial, ignoring trailing zero coefficients.
+    // The zero polynomial has no degree and returns `None`.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.iter().rposition(|c| !c.is_zero())
+    }
+
+    // Returns whether all the coefficients are zero.
+    pub fn is_zero(&self) -> bool {
+        self.degree().is_none()
+    }
+
+    // Removes the trailing zero coefficients, so that the last coefficient is the leading one.
+    pub fn normalize(&mut self) {
+        let len = self.degree().map_or(0, |d| d + 1);
+        self.coeffs.truncate(len);
+    }
+
+    // Divides the polynomial by `divisor` with long division.
+    // Returns the quotient `q` and the remainder `r` such that `self = q * divisor + r`
+    // and `r` has a lower degree than `divisor`.
+    pub fn div_rem(&self, divisor: &Polynomial<F>) -> (Polynomial<F>, Polynomial<F>) {
+        let divisor_degree = divisor
+            .degree()
+            .expect("cannot divide by the zero polynomial");
+        let leading_inverse = divisor.coeffs[divisor_degree].inverse();
+
+        let mut remainder = self.clone();
+        remainder.normalize();
+        let quotient_len = (remainder.coeffs.len() + 1).saturating_sub(divisor_degree + 1);
+        let mut quotient = vec![leading_inverse.zero(); quotient_len];
+
+        // cancel the leading coefficient of the remainder until its degree drops below the divisor's
+        for shift in (0..quotient_len).rev() {
+            let factor = remainder.coeffs[shift + divisor_degree] * leading_inverse;
+            quotient[shift] = factor;
+            for (i, c) in divisor.coeffs[..=divisor_degree].iter().enumerate() {
+                remainder.coeffs[shift + i] = remainder.coeffs[shift + i] - factor * *c;
+            }
         }
+        remainder.normalize();
+
+        (Polynomial::new(quotient), remainder)
+    }
+
+    // Multiplies two polynomials coefficient by coefficient, in O(n * m) field operations.
+    fn mul_schoolbook(&self, other: &Polynomial<F>) -> Polynomial<F> {
+        let mut coeffs = vec![self.coeffs[0].zero(); self.coeffs.len() + other.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in other.coeffs.iter().enumerate() {
+                coeffs[i + j] = coeffs[i + j] + *a * *b;
+            }
+        }
+
+        Polynomial::new(coeffs)
+    }
+
+    // Multiplies two polynomials by evaluating both over a large enough power-of-two subgroup,
+    // multiplying pointwise and interpolating back. Returns `None` if the field has no such subgroup.
+    fn mul_ntt(&self, other: &Polynomial<F>) -> Option<Polynomial<F>> {
+        let one = self.coeffs[0].one();
+        let size = (self.coeffs.len() + other.coeffs.len() - 1).next_power_of_two();
+        let domain = EvaluationDomain::from_size(size, one).ok()?;
 
-        value
+        let ntt = Ntt::new(&domain);
+        let left = self.evaluate_coset_with(&ntt, &domain);
+        let right = other.evaluate_coset_with(&ntt, &domain);
+        let product: Vec<F> = left
+            .iter()
+            .zip(right.iter())
+            .map(|(a, b)| *a * *b)
+            .collect();
+
+        Some(Polynomial::interpolate_with(&ntt, &domain, &product))
+    }
+
+    // Returns the degree bound of the polynomial: the smallest power of two strictly greater
+    // than its degree, i.e. the number of coefficients rounded up to a power of two.
+    pub fn degree_bound(&self) -> usize {
+        self.coeffs.len().max(1).next_power_of_two()
     }
 
-    // Evaluates the polynomial over an entire domain of points (a vector of `FieldElement`s).
+    // Evaluates the polynomial at a given point `x` (which is an element of `F`).
+    // This function implements Horner's method, folding the coefficients from the
+    // highest degree down so that each step costs one multiplication and one addition.
+    pub fn evaluate(&self, x: F) -> F {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(x.zero(), |value, c| value * x + *c)
+    }
+
+    // Evaluates the polynomial over an entire domain of points (a vector of elements of `F`).
     // Returns a vector of the results for each point in the domain.
-    pub fn evaluate_domain(&self, domain: &Vec<FieldElement>) -> Vec<FieldElement> {
+    pub fn evaluate_domain(&self, domain: &Vec<F>) -> Vec<F> {
         let mut output = Vec::with_capacity(domain.len());
         for x in domain {
             let res = self.evaluate(*x);
@@ -39,6 +192,192 @@ impl Polynomial {
 
         output
     }
+
+    // Evaluates the polynomial over the coset `offset * <generator>` described by `domain`.
+    // Power-of-two domains use the NTT: the coefficients are scaled by powers of the offset,
+    // wrapped modulo `X^n - 1` when the degree exceeds the domain, then transformed in place.
+    // Other domain sizes fall back to evaluating every point with Horner's method.
+    pub fn evaluate_coset(&self, domain: &EvaluationDomain<F>) -> Vec<F> {
+        if !domain.size.is_power_of_two() {
+            return self.evaluate_domain(&domain.elements());
+        }
+
+        self.evaluate_coset_with(&Ntt::new(domain), domain)
+    }
+
+    // Evaluates like `evaluate_coset` over a power-of-two domain, with the transform of its subgroup
+    // built by the caller, so that repeated evaluations of that size share the twiddle factors.
+    pub fn evaluate_coset_with(&self, ntt: &Ntt<F>, domain: &EvaluationDomain<F>) -> Vec<F> {
+        assert!(ntt.matches(domain), "the NTT does not match the domain");
+        let mut values = vec![domain.offset.zero(); domain.size];
+        let mut offset_power = domain.offset.one();
+        for (i, c) in self.coeffs.iter().enumerate() {
+            values[i % domain.size] = values[i % domain.size] + *c * offset_power;
+            offset_power = offset_power * domain.offset;
+        }
+
+        ntt.forward(&mut values);
+
+        values
+    }
+}
+
+// Two polynomials are equal if their coefficients agree once trailing zeros are ignored.
+impl<F: PrimeField> PartialEq for Polynomial<F> {
+    fn eq(&self, other: &Self) -> bool {
+        let len = self.degree().map_or(0, |d| d + 1);
+        let other_len = other.degree().map_or(0, |d| d + 1);
+
+        self.coeffs[..len] == other.coeffs[..other_len]
+    }
+}
+
+// Implements the `Add` trait to enable addition of two polynomials, coefficient by coefficient.
+impl<F: PrimeField> Add for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let (long, short) = if self.coeffs.len() >= rhs.coeffs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        let mut coeffs = long.coeffs.clone();
+        for (c, s) in coeffs.iter_mut().zip(short.coeffs.iter()) {
+            *c = *c + *s;
+        }
+
+        let mut sum = Polynomial::new(coeffs);
+        sum.normalize();
+        sum
+    }
+}
+
+impl<F: PrimeField> Add for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+// Implements the `Neg` trait by negating every coefficient.
+impl<F: PrimeField> Neg for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn neg(self) -> Self::Output {
+        Polynomial::new(self.coeffs.iter().map(|c| -*c).collect())
+    }
+}
+
+impl<F: PrimeField> Neg for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+// Implements the `Sub` trait as the addition of the negated right-hand side.
+impl<F: PrimeField> Sub for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + &(-rhs)
+    }
+}
+
+impl<F: PrimeField> Sub for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        &self - &rhs
+    }
+}
+
+// Implements the `Mul` trait for the product of two polynomials.
+// Small operands use schoolbook multiplication, large ones go through the NTT when the
+// field has a large enough power-of-two subgroup.
+impl<F: PrimeField> Mul for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut left = self.clone();
+        let mut right = rhs.clone();
+        left.normalize();
+        right.normalize();
+        if left.is_zero() || right.is_zero() {
+            return Polynomial::new(vec![]);
+        }
+
+        let mut product =
+            if left.coeffs.len().min(right.coeffs.len()) <= NTT_MULTIPLICATION_THRESHOLD {
+                left.mul_schoolbook(&right)
+            } else {
+                left.mul_ntt(&right)
+                    .unwrap_or_else(|| left.mul_schoolbook(&right))
+            };
+        product.normalize();
+        product
+    }
+}
+
+impl<F: PrimeField> Mul for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+// Implements the `Mul` trait for the product of a polynomial with a scalar.
+impl<F: PrimeField> Mul<F> for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn mul(self, rhs: F) -> Self::Output {
+        let mut product = Polynomial::new(self.coeffs.iter().map(|c| *c * rhs).collect());
+        product.normalize();
+        product
+    }
+}
+
+impl<F: PrimeField> Mul<F> for Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn mul(self, rhs: F) -> Self::Output {
+        &self * rhs
+    }
+}
+
+/// Computes the low-degree extension of a polynomial, its Reed-Solomon codeword.
+///
+/// # Arguments
+///
+/// * `poly` - The polynomial to encode.
+/// * `blowup` - The inverse of the code rate, a power of two of at least 2 (rate 1/2, 1/4, 1/8, ...).
+/// * `offset` - The coset offset, usually a generator of the field so the domain avoids the subgroup.
+///
+/// # Returns
+///
+/// The coset `offset * <w>` of size `blowup * poly.degree_bound()` and the evaluations of `poly` over it.
+pub fn low_degree_extend<F: PrimeField>(
+    poly: &Polynomial<F>,
+    blowup: usize,
+    offset: F,
+) -> Result<(EvaluationDomain<F>, Vec<F>), FriError> {
+    if blowup < 2 || !blowup.is_power_of_two() {
+        return Err(FriError::InvalidBlowup { blowup });
+    }
+
+    let size = poly
+        .degree_bound()
+        .checked_mul(blowup)
+        .ok_or(FriError::InvalidBlowup { blowup })?;
+    let domain = EvaluationDomain::from_size(size, offset)?;
+    let codeword = poly.evaluate_coset(&domain);
+
+    Ok((domain, codeword))
 }
 
 /// Performs polynomial folding on a given set of coefficients.
@@ -47,29 +386,30 @@ impl Polynomial {
 ///
 /// * `poly` - The input polynomial to be folded.
 /// * `beta` - A random field element used for folding.
+/// * `folding_factor` - The number of cosets `k` the coefficients are split into.
 ///
 /// # Returns
 ///
-/// A new `Polynomial` instance where the even-indexed and odd-indexed coefficients are combined using the folding technique.
-pub fn fold_polynomial(poly: &Polynomial, beta: &FieldElement) -> Polynomial {
-    let coeffs = poly.coeffs.clone();
-
-    // split polynomial into even and odd indexes,
-    // multiply the odd indexes by beta and return the vector of folded polynomial coefficients
-    let even: Vec<FieldElement> = coeffs.iter().step_by(2).cloned().collect();
-    let odd: Vec<FieldElement> = coeffs
-        .iter()
-        .skip(1)
-        .step_by(2)
-        .cloned()
-        .map(|o| o * *beta) //Multiply odd-indexed coefficients by `beta`.
-        .collect();
+/// A new `Polynomial` instance where, writing `poly(x) = sum_t x^t f_t(x^k)`, the coefficients
+/// of the `k` polynomials `f_t` are combined as `sum_t beta^t f_t`.
+pub fn fold_polynomial<F: PrimeField>(
+    poly: &Polynomial<F>,
+    beta: &F,
+    folding_factor: usize,
+) -> Polynomial<F> {
+    assert!(folding_factor >= 2, "folding factor must be at least 2");
 
-    // Combine the even and odd coefficients by adding corresponding elements.
-    let coeffs = even
-        .iter()
-        .zip(odd.iter())
-        .map(|(even, odd)| *even + *odd)
+    // Coefficient `j` of the folded polynomial gathers the `k` coefficients `j * k + t`,
+    // each multiplied by `beta^t`. A missing trailing coefficient counts as zero.
+    let coeffs = poly
+        .coeffs
+        .chunks(folding_factor)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .rev()
+                .fold(beta.zero(), |acc, coeff| acc * *beta + *coeff)
+        })
         .collect();
 
     // Return the new folded polynomial.
@@ -79,7 +419,7 @@ pub fn fold_polynomial(poly: &Polynomial, beta: &FieldElement) -> Polynomial {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Field, FieldElement};
+    use crate::{Field, FieldElement, FriError};
 
     #[test]
     fn polynomial_evaluation() {
@@ -113,4 +453,230 @@ mod tests {
         assert_eq!(res[0], FieldElement::new(1, field));
         assert_eq!(res[1], FieldElement::new(6, field));
     }
+
+    #[test]
+    fn interpolate_round_trips_over_cosets() {
+        let field = Field::new(998244353);
+        let coeffs: Vec<FieldElement> = (0..16)
+            .map(|c| FieldElement::new(c * c + 1, field))
+            .collect();
+        let poly = Polynomial::new(coeffs.clone());
+
+        for offset in [field.one(), field.generator()] {
+            let domain = EvaluationDomain::from_size(16, offset).unwrap();
+            let values = poly.evaluate_domain(&domain.elements());
+
+            let interpolated = Polynomial::interpolate(&domain, &values);
+            assert_eq!(interpolated.coeffs, coeffs);
+        }
+
+        // a domain of size 7 goes through the general interpolation
+        let domain = EvaluationDomain::from_size(7, field.generator()).unwrap();
+        let small = Polynomial::new(coeffs[..7].to_vec());
+        let values = small.evaluate_domain(&domain.elements());
+        assert_eq!(
+            Polynomial::interpolate(&domain, &values).coeffs,
+            small.coeffs
+        );
+    }
+
+    #[test]
+    fn interpolate_arbitrary_points() {
+        let field = Field::new(97);
+        let coeffs: Vec<FieldElement> = [5, 0, 3, 96, 12]
+            .iter()
+            .map(|c| FieldElement::new(*c, field))
+            .collect();
+        let poly = Polynomial::new(coeffs.clone());
+        let xs: Vec<FieldElement> = [3, 17, 2, 50, 91]
+            .iter()
+            .map(|x| FieldElement::new(*x, field))
+            .collect();
+        let ys = poly.evaluate_domain(&xs);
+
+        let interpolated = Polynomial::interpolate_points(&xs, &ys);
+        assert_eq!(interpolated.coeffs, coeffs);
+
+        // a single point gives the constant polynomial
+        let constant = Polynomial::interpolate_points(&xs[..1], &ys[..1]);
+        assert_eq!(constant.coeffs, vec![ys[0]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "interpolation points must be distinct")]
+    fn interpolate_rejects_repeated_points() {
+        let field = Field::new(97);
+        let x = FieldElement::new(4, field);
+        Polynomial::interpolate_points(&[x, x], &[field.one(), field.zero()]);
+    }
+
+    fn poly(field: Field, coeffs: &[i128]) -> Polynomial<FieldElement> {
+        Polynomial::new(
+            coeffs
+                .iter()
+                .map(|c| FieldElement::new(*c, field))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn polynomial_degree_ignores_trailing_zeros() {
+        let field = Field::new(97);
+
+        assert_eq!(poly(field, &[1, 2, 3, 0, 0]).degree(), Some(2));
+        assert_eq!(poly(field, &[0, 0]).degree(), None);
+        assert_eq!(poly(field, &[]).degree(), None);
+        assert_eq!(poly(field, &[1, 2, 3, 0, 0]), poly(field, &[1, 2, 3]));
+        assert_ne!(poly(field, &[1, 2, 3]), poly(field, &[1, 2, 4]));
+        assert_eq!(poly(field, &[0]), poly(field, &[]));
+    }
+
+    #[test]
+    fn polynomial_add_sub_scalar() {
+        let field = Field::new(97);
+        let a = poly(field, &[1, 2, 3]);
+        let b = poly(field, &[96, 5, 94, 1]);
+
+        assert_eq!(&a + &b, poly(field, &[0, 7, 0, 1]));
+        assert_eq!(&a - &b, poly(field, &[2, 94, 6, 96]));
+        assert_eq!(&a - &a, poly(field, &[]));
+        assert_eq!(&a * FieldElement::new(2, field), poly(field, &[2, 4, 6]));
+        assert_eq!(-a.clone() + a, poly(field, &[]));
+    }
+
+    #[test]
+    fn polynomial_multiplication() {
+        let field = Field::new(97);
+        // (1 + x)(1 - x) = 1 - x^2
+        assert_eq!(
+            poly(field, &[1, 1]) * poly(field, &[1, 96]),
+            poly(field, &[1, 0, 96])
+        );
+        assert_eq!(poly(field, &[1, 1]) * poly(field, &[]), poly(field, &[]));
+
+        // large operands go through the NTT and must agree with schoolbook multiplication
+        let field = Field::new(998244353);
+        let a = Polynomial::new(
+            (0..100)
+                .map(|c| FieldElement::new(c * 7 + 1, field))
+                .collect(),
+        );
+        let b = Polynomial::new(
+            (0..90)
+                .map(|c| FieldElement::new(c * c + 3, field))
+                .collect(),
+        );
+        let product = &a * &b;
+
+        assert_eq!(product.degree(), Some(188));
+        assert_eq!(product, a.mul_schoolbook(&b));
+        let x = FieldElement::new(12345, field);
+        assert_eq!(product.evaluate(x), a.evaluate(x) * b.evaluate(x));
+    }
+
+    #[test]
+    fn polynomial_div_rem() {
+        let field = Field::new(97);
+        let a = poly(field, &[5, 0, 3, 96, 12, 7]);
+        let b = poly(field, &[2, 0, 1]);
+
+        let (q, r) = a.div_rem(&b);
+        assert!(r.degree() < b.degree());
+        assert_eq!(&(&q * &b) + &r, a);
+
+        // exact division by a linear factor (x - 3) of a polynomial vanishing at 3
+        let root = FieldElement::new(3, field);
+        let linear = poly(field, &[94, 1]);
+        let multiple = &a * &linear;
+        let (q, r) = multiple.div_rem(&linear);
+        assert!(r.is_zero());
+        assert_eq!(q, a);
+        assert_eq!(multiple.evaluate(root), field.zero());
+
+        // dividing by a polynomial of larger degree leaves everything in the remainder
+        let (q, r) = b.div_rem(&a);
+        assert!(q.is_zero());
+        assert_eq!(r, b);
+    }
+
+    #[test]
+    fn low_degree_extension_rates() {
+        let field = Field::new(998244353);
+        let offset = field.generator();
+        let coeffs = (0..7).map(|c| FieldElement::new(c + 3, field)).collect();
+        let poly = Polynomial::new(coeffs);
+        assert_eq!(poly.degree_bound(), 8);
+
+        for blowup in [2, 4, 8, 16] {
+            let (domain, codeword) = low_degree_extend(&poly, blowup, offset).unwrap();
+
+            assert_eq!(domain.size, 8 * blowup);
+            assert_eq!(domain.offset, offset);
+            assert_eq!(codeword, poly.evaluate_domain(&domain.elements()));
+        }
+
+        for blowup in [0, 1, 3, 6] {
+            assert_eq!(
+                low_degree_extend(&poly, blowup, offset).unwrap_err(),
+                FriError::InvalidBlowup { blowup }
+            );
+        }
+    }
+
+    #[test]
+    fn polynomial_evaluation_coset() {
+        let field = Field::new(97);
+        let coeffs = (0..20)
+            .map(|c| FieldElement::new(c * 5 + 1, field))
+            .collect();
+        let poly = Polynomial::new(coeffs);
+
+        // degree above the domain size exercises the wrap-around modulo X^n - 1
+        let offset = FieldElement::new(5, field);
+        for size in [1, 2, 8, 32, 3] {
+            let domain = EvaluationDomain::from_size(size, offset).unwrap();
+            assert_eq!(
+                poly.evaluate_coset(&domain),
+                poly.evaluate_domain(&domain.elements())
+            );
+        }
+    }
+
+    #[test]
+    fn fold_polynomial_keeps_trailing_coefficient() {
+        let field = Field::new(97);
+        let beta = FieldElement::new(3, field);
+
+        // 1 + 2x + 3x^2 folds to (1 + 3 * 2) + 3x, the odd length must not drop 3x^2
+        let folded = fold_polynomial(&poly(field, &[1, 2, 3]), &beta, 2);
+        assert_eq!(folded, poly(field, &[7, 3]));
+
+        // folding by 4 gathers c0 + beta c1 + beta^2 c2 + beta^3 c3 per coefficient
+        let folded = fold_polynomial(&poly(field, &[1, 2, 3, 4, 5]), &beta, 4);
+        assert_eq!(folded, poly(field, &[1 + 6 + 27 + 108, 5]));
+    }
+
+    #[test]
+    fn fold_polynomial_matches_coset_decomposition() {
+        let field = Field::new(97);
+        let p = poly(field, &[19, 56, 34, 48, 43, 37, 10, 5, 8, 2, 77]);
+        let beta = FieldElement::new(11, field);
+        let x = FieldElement::new(6, field);
+
+        // p(x) = sum_t x^t f_t(x^k), so the k-th roots of unity z give
+        // sum_t beta^t f_t(x^k) = (1/k) sum_j p(x z^j) sum_t (beta / (x z^j))^t
+        for k in [2, 4, 8, 16] {
+            let folded = fold_polynomial(&p, &beta, k);
+            let z = field.one().primitive_nth_root(k as u128).unwrap();
+            let mut expected = field.zero();
+            for j in 0..k {
+                let point = x * z.pow(j as u128);
+                let ratio = beta / point;
+                let weight = (0..k).fold(field.zero(), |acc, t| acc + ratio.pow(t as u128));
+                expected = expected + p.evaluate(point) * weight;
+            }
+            let expected = expected / FieldElement::new(k as i128, field);
+            assert_eq!(folded.evaluate(x.pow(k as u128)), expected);
+        }
+    }
 }
@@ -0,0 +1,212 @@
+use crate::{factorize, FriError, Hashable};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::sync::{Mutex, OnceLock};
+
+/// The `PrimeField` trait is implemented by the field elements FRI runs over.
+/// Some implementations, such as `FieldElement`, only know their modulus at runtime, so constants
+/// and challenges are always derived from an element of the field, `self`, rather than from the type alone.
+/// Implementations with a fixed modulus simply ignore `self` in those methods.
+pub trait PrimeField:
+    Copy
+    + Debug
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + Hashable
+    + Serialize
+    + DeserializeOwned
+{
+    // Returns the additive identity of the field `self` belongs to.
+    fn zero(&self) -> Self;
+
+    // Returns the multiplicative identity of the field `self` belongs to.
+    fn one(&self) -> Self;
+
+    // Returns the integer `n` as an element of the field `self` belongs to, reduced modulo the prime.
+    fn integer(&self, n: u64) -> Self;
+
+    // Returns whether `self` is the additive identity.
+    fn is_zero(&self) -> bool;
+
+    // Returns whether `self` is stored in its canonical, reduced form.
+    fn is_reduced(&self) -> bool;
+
+    // Returns the order of the multiplicative group of the field, `p - 1`.
+    fn group_order(&self) -> u128;
+
+    // Returns a generator of the multiplicative group of the field.
+    fn generator(&self) -> Self;
+
+    // Returns the canonical encoding of `self`, the bytes absorbed into a transcript.
+    fn to_bytes(&self) -> Vec<u8>;
+
+    // Maps uniformly random bytes, such as a transcript squeeze, to an element of the field of `self`
+    // with negligible bias.
+    fn sample(&self, bytes: &[u8]) -> Self;
+
+    // Returns whether `self` and `other` belong to the same field.
+    // Types with a fixed modulus always do, so only types with a runtime modulus override it.
+    fn same_field(&self, _other: &Self) -> bool {
+        true
+    }
+
+    // Returns whether `self` is a reduced element of the field `field` belongs to.
+    // Elements received from the outside, such as in a proof, must be checked before use.
+    fn is_reduced_in(&self, field: &Self) -> bool {
+        self.same_field(field) && self.is_reduced()
+    }
+
+    // Returns the multiplicative inverse of `self`, which must be nonzero.
+    // By Fermat's little theorem it is `self^(p - 2)`; fields with a faster inversion override it.
+    fn inverse(&self) -> Self {
+        assert!(!self.is_zero(), "cannot invert 0");
+
+        self.pow(self.group_order() - 1)
+    }
+
+    // Computes `self^exponent` by square-and-multiply.
+    fn pow(&self, exponent: u128) -> Self {
+        let mut result = self.one();
+        let mut base = *self;
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            exponent >>= 1;
+        }
+
+        result
+    }
+
+    // Returns the two-adicity of the field: the largest `k` such that `2^k` divides `p - 1`.
+    // This bounds the size of the power-of-two domains the field supports.
+    fn two_adicity(&self) -> u32 {
+        self.group_order().trailing_zeros()
+    }
+
+    // Returns a primitive nth root of unity, an element of exact multiplicative order `n`.
+    // Such an element exists iff `n` divides `p - 1`, in which case it is `g^((p - 1) / n)`
+    // for a generator `g` of the multiplicative group.
+    fn primitive_nth_root(&self, n: u128) -> Result<Self, FriError> {
+        let order = self.group_order();
+        if n == 0 || !order.is_multiple_of(n) {
+            return Err(FriError::NoRootOfUnity { order: n });
+        }
+
+        Ok(self.generator().pow(order / n))
+    }
+}
+
+// Smallest generators already found, as integers, by group order `p - 1`. The smallest generator is the
+// same integer in every representation of the field, so `p - 1` is factorized once per prime.
+static SMALLEST_GENERATORS: OnceLock<Mutex<HashMap<u128, u64>>> = OnceLock::new();
+
+// Returns the smallest generator of the multiplicative group of the field of `one`, trying 1, 2, 3, ...
+// An element generates the group iff `g^((p - 1) / q) != 1` for every prime factor `q` of `p - 1`.
+// The search runs on the first call for a prime, later calls return the memoized generator.
+pub fn smallest_generator<F: PrimeField>(one: &F) -> F {
+    let order = one.group_order();
+    let generators = SMALLEST_GENERATORS.get_or_init(Default::default);
+    if let Some(&generator) = generators.lock().unwrap().get(&order) {
+        return one.integer(generator);
+    }
+
+    let factors = factorize(order);
+    let generator = (1..)
+        .find(|&candidate| {
+            let candidate = one.integer(candidate);
+            factors
+                .iter()
+                .all(|(q, _)| candidate.pow(order / q) != *one)
+        })
+        .expect("the multiplicative group of a prime field is cyclic");
+    generators.lock().unwrap().insert(order, generator);
+
+    one.integer(generator)
+}
+
+// Returns the multiplicative inverses of all `values` with a single field inversion,
+// using Montgomery's trick: invert the product of all values, then peel off one factor at a time.
+// Every value must be nonzero.
+pub fn batch_inverse<F: PrimeField>(values: &[F]) -> Vec<F> {
+    let Some(first) = values.first() else {
+        return vec![];
+    };
+
+    // prefix[i] is the product of the values before index i
+    let mut prefix = Vec::with_capacity(values.len());
+    let mut product = first.one();
+    for value in values {
+        assert!(!value.is_zero(), "cannot invert 0");
+        prefix.push(product);
+        product = product * *value;
+    }
+
+    let mut inverse = product.inverse();
+    let mut inverses = vec![first.zero(); values.len()];
+    for (i, value) in values.iter().enumerate().rev() {
+        inverses[i] = inverse * prefix[i];
+        inverse = inverse * *value;
+    }
+
+    inverses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Field, FieldElement, MontgomeryField};
+
+    #[test]
+    fn trait_methods_agree_with_field_element() {
+        let field = Field::new(97);
+        let x = FieldElement::new(35, field);
+
+        assert_eq!(x.integer(100), FieldElement::new(3, field));
+        assert_eq!(x * PrimeField::inverse(&x), x.one());
+        assert_eq!(PrimeField::pow(&x, 96), x.one());
+        assert_eq!(x.two_adicity(), 5);
+        assert!(!x.is_reduced_in(&Field::new(101).one()));
+
+        let root = x.primitive_nth_root(32).unwrap();
+        assert_eq!(PrimeField::pow(&root, 16), -x.one());
+        assert_eq!(
+            x.primitive_nth_root(64).unwrap_err(),
+            FriError::NoRootOfUnity { order: 64 }
+        );
+    }
+
+    #[test]
+    fn smallest_generator_is_found_once_per_prime() {
+        let field = Field::new(193);
+        let generator = smallest_generator(&field.one());
+        assert_eq!(generator, FieldElement::new(5, field));
+        assert_eq!(SMALLEST_GENERATORS.get().unwrap().lock().unwrap()[&192], 5);
+
+        // the memoized integer is the generator in every representation of the field
+        let montgomery = MontgomeryField::new(193);
+        assert_eq!(smallest_generator(&montgomery.one()).value(), 5);
+        assert_eq!(montgomery.generator(), montgomery.element(5));
+    }
+
+    #[test]
+    fn batch_inverse_matches_single_inversions() {
+        let field = Field::new(97);
+        let values: Vec<FieldElement> = (1..20).map(|n| FieldElement::new(n * 5, field)).collect();
+
+        let inverses = batch_inverse(&values);
+        for (value, inverse) in values.iter().zip(inverses.iter()) {
+            assert_eq!(*inverse, PrimeField::inverse(value));
+        }
+        assert!(batch_inverse::<FieldElement>(&[]).is_empty());
+    }
+}
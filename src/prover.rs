@@ -1,14 +1,24 @@
-use crate::{Field, FieldElement};
-use rand::Rng;
+use crate::PrimeField;
 use sha2::{Digest, Sha256};
 
+// Domain separation tags for the different hash invocations of the sponge.
+const ABSORB_TAG: u8 = 0x00;
+const SQUEEZE_TAG: u8 = 0x01;
+const OUTPUT_TAG: u8 = 0x02;
+const GRINDING_TAG: u8 = 0x03;
+
 /// The `ProofStream` struct is used to simulate a transcript between the prover and verifier
 /// in an interactive proof system. It stores a sequence of objects (typically commitments or queries),
 /// and supports pushing new objects or pulling previously pushed ones in sequence.
+///
+/// It also acts as a sponge for the Fiat-Shamir heuristic: every message is absorbed under a label,
+/// and challenges are squeezed out of the running hash state. The prover and the verifier call
+/// `absorb` and `squeeze_*` in the same order with the same labels, so they derive the same challenges.
 #[derive(Default)]
 pub struct ProofStream {
     pub objects: Vec<Vec<u8>>,
     pub read_index: i64,
+    state: [u8; 32], // Running hash of everything absorbed and squeezed so far.
 }
 
 impl ProofStream {
@@ -17,13 +27,14 @@ impl ProofStream {
         Self {
             objects: vec![],
             read_index: 0,
+            state: [0u8; 32],
         }
     }
 
     // Adds a new object (byte array) to the proof stream.
     // This simulates the prover pushing data into the proof stream.
-    pub fn push(&mut self, object: &Vec<u8>) {
-        self.objects.push(object.clone());
+    pub fn push(&mut self, object: &[u8]) {
+        self.objects.push(object.to_vec());
     }
 
     // Retrieves the next object from the proof stream, advancing the read index.
@@ -54,39 +65,159 @@ impl ProofStream {
         ps
     }
 
-    // Prover's Fiat-Shamir heuristic.
-    // The prover hashes the current state of the proof stream to generate a challenge
-    // in the form of a `FieldElement`. This method simulates the prover's Fiat-Shamir process.
-    pub fn prover_fiat_shamir(&self, field: &Field) -> FieldElement {
+    // Absorbs a message sent by the prover into the sponge state under a domain-separation label,
+    // and records it in the stream. Label and message are length-prefixed so that
+    // different splits of the same bytes never produce the same state.
+    pub fn absorb(&mut self, label: &str, bytes: &[u8]) {
         let mut hasher = Sha256::new();
-        hasher.update(self.serialize().as_bytes());
+        hasher.update([ABSORB_TAG]);
+        hasher.update(self.state);
+        hasher.update((label.len() as u64).to_be_bytes());
+        hasher.update(label.as_bytes());
+        hasher.update((bytes.len() as u64).to_be_bytes());
+        hasher.update(bytes);
+        self.state = hasher.finalize().into();
+
+        self.push(bytes);
+    }
 
-        let result = hasher.finalize();
+    // Squeezes 32 pseudorandom bytes out of the sponge under a domain-separation label.
+    // The state is ratcheted forward, so consecutive squeezes return different outputs.
+    pub fn squeeze_bytes(&mut self, label: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update([SQUEEZE_TAG]);
+        hasher.update(self.state);
+        hasher.update((label.len() as u64).to_be_bytes());
+        hasher.update(label.as_bytes());
+        self.state = hasher.finalize().into();
 
-        // return a field element from bytes
-        FieldElement::from_bytes(&result, *field)
+        let mut hasher = Sha256::new();
+        hasher.update([OUTPUT_TAG]);
+        hasher.update(self.state);
+
+        hasher.finalize().into()
     }
 
-    pub fn verifier_fiat_shamir(&self, field: &Field) -> FieldElement {
-        let slice = &self.objects[self.read_index as usize];
-        let binding = serde_json::to_string(slice).expect("Serialization failed");
-        let data = binding.as_bytes();
+    // Squeezes a challenge in the form of an element of the field `field` belongs to.
+    // The 32 squeezed bytes are mapped to the field by `PrimeField::sample`.
+    pub fn squeeze_field_element<F: PrimeField>(&mut self, label: &str, field: &F) -> F {
+        let bytes = self.squeeze_bytes(label);
+
+        field.sample(&bytes)
+    }
+
+    // Squeezes `number` pseudorandom indices in `0..domain_size`.
+    // The indices only depend on what was absorbed so far, so the prover and the verifier sample
+    // the same query positions once all layer roots and the last value are in the stream.
+    // Each candidate is read from a fresh squeeze and rejected if it falls in the
+    // incomplete top range of `u64`, so the result is not biased by the modulo reduction.
+    pub fn squeeze_indices(
+        &mut self,
+        label: &str,
+        number: usize,
+        domain_size: usize,
+    ) -> Vec<usize> {
+        assert!(
+            domain_size > 0,
+            "Cannot sample indices from an empty domain."
+        );
+
+        let modulus = domain_size as u128;
+        let zone = (1u128 << 64) - (1u128 << 64) % modulus;
+
+        let mut indices = Vec::with_capacity(number);
+        while indices.len() < number {
+            let bytes = self.squeeze_bytes(label);
+
+            let mut array = [0u8; 8];
+            array.copy_from_slice(&bytes[0..8]);
+            let candidate = u64::from_be_bytes(array) as u128;
 
+            // reject candidates that would make lower indices more likely
+            if candidate < zone {
+                indices.push((candidate % modulus) as usize);
+            }
+        }
+
+        indices
+    }
+
+    // Searches for the smallest proof-of-work nonce such that hashing it with the current state
+    // under `label` gives at least `bits` leading zero bits. The state is left unchanged, the nonce
+    // is expected to be absorbed afterwards so that the following challenges depend on it.
+    pub fn grind(&self, label: &str, bits: u32) -> u64 {
+        (0..=u64::MAX)
+            .find(|&nonce| self.check_grinding(label, nonce, bits))
+            .expect("no proof-of-work nonce found")
+    }
+
+    // Checks a proof-of-work nonce produced by `grind` against the current state.
+    pub fn check_grinding(&self, label: &str, nonce: u64, bits: u32) -> bool {
         let mut hasher = Sha256::new();
-        hasher.update(data);
+        hasher.update([GRINDING_TAG]);
+        hasher.update(self.state);
+        hasher.update((label.len() as u64).to_be_bytes());
+        hasher.update(label.as_bytes());
+        hasher.update(nonce.to_be_bytes());
+        let digest: [u8; 32] = hasher.finalize().into();
+
+        let mut array = [0u8; 8];
+        array.copy_from_slice(&digest[0..8]);
+        u64::from_be_bytes(array).leading_zeros() >= bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Field;
+
+    #[test]
+    fn prover_and_verifier_derive_same_challenges() {
+        let field = Field::new(97).one();
+        let mut prover = ProofStream::new();
+        let mut verifier = ProofStream::new();
+
+        prover.absorb("root", &[1, 2, 3]);
+        let prover_alpha = prover.squeeze_field_element("alpha", &field);
+        prover.absorb("last", &[4, 5, 6]);
+        let prover_indices = prover.squeeze_indices("query", 16, 64);
+
+        verifier.absorb("root", &[1, 2, 3]);
+        let verifier_alpha = verifier.squeeze_field_element("alpha", &field);
+        verifier.absorb("last", &[4, 5, 6]);
+        let verifier_indices = verifier.squeeze_indices("query", 16, 64);
+
+        assert_eq!(prover_alpha, verifier_alpha);
+        assert_eq!(prover_indices, verifier_indices);
+        assert!(prover_indices.iter().all(|i| *i < 64));
+    }
+
+    #[test]
+    fn squeeze_depends_on_transcript_and_label() {
+        let mut first = ProofStream::new();
+        first.absorb("root", &[1, 2, 3]);
+
+        let mut second = ProofStream::new();
+        second.absorb("root", &[1, 2, 4]);
 
-        let result = hasher.finalize();
+        let mut third = ProofStream::new();
+        third.absorb("root", &[1, 2, 3]);
 
-        // return a field element from bytes
-        FieldElement::from_bytes(&result, *field)
+        let first_bytes = first.squeeze_bytes("alpha");
+        assert_ne!(first_bytes, second.squeeze_bytes("alpha"));
+        assert_ne!(first_bytes, third.squeeze_bytes("beta"));
+        assert_ne!(first_bytes, first.squeeze_bytes("alpha"));
     }
 
-    // Generates a pseudorandom index
-    pub fn verifier_random_index(&mut self, domain_size: usize) -> usize {
-        let mut rng = rand::thread_rng();
-        let num: usize = rng.gen();
+    #[test]
+    fn grinding_nonce_is_checked_against_the_state() {
+        let mut transcript = ProofStream::new();
+        transcript.absorb("root", &[1, 2, 3]);
 
-        // Return the result mod domain_size to fit within the valid index range
-        num % domain_size
+        let nonce = transcript.grind("pow", 8);
+        assert!(transcript.check_grinding("pow", nonce, 8));
+        assert!((0..nonce).all(|n| !transcript.check_grinding("pow", n, 8)));
+        assert_eq!(transcript.grind("pow", 0), 0);
     }
 }
@@ -0,0 +1,204 @@
+use crate::FriParams;
+
+// Smallest and largest Johnson proximity parameter `m` tried by the provable estimate.
+const MIN_PROXIMITY_PARAMETER: usize = 3;
+const MAX_PROXIMITY_PARAMETER: usize = 64;
+
+/// The `SoundnessBound` enum selects which analysis of FRI a security level refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundnessBound {
+    // Assumes the proximity gaps conjecture: each query catches a cheating prover with probability `1 - rho`.
+    Conjectured,
+    // Uses the proven bounds up to the Johnson radius, with a query error of about `sqrt(rho)`.
+    Provable,
+}
+
+/// The `SecurityEstimate` struct reports the bits of security of a FRI configuration under both analyses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SecurityEstimate {
+    pub conjectured_bits: f64,
+    pub provable_bits: f64,
+}
+
+impl SecurityEstimate {
+    // Estimates the security of `params` over a field of `field_bits` bits, the log2 of its size.
+    // The hash output size is taken from `params.hash()`.
+    pub fn new(params: &FriParams, field_bits: f64) -> Self {
+        Self {
+            conjectured_bits: conjectured_security(params, field_bits),
+            provable_bits: provable_security(params, field_bits),
+        }
+    }
+
+    // Returns the bits of security under the chosen analysis.
+    pub fn bits(&self, bound: SoundnessBound) -> f64 {
+        match bound {
+            SoundnessBound::Conjectured => self.conjectured_bits,
+            SoundnessBound::Provable => self.provable_bits,
+        }
+    }
+}
+
+// Returns the conjectured bits of security of `params`, the minimum of
+// - the query phase, `number_of_queries * log2(blowup) + grinding_bits`,
+// - the commit phase, `field_bits - log2(domain_size)`,
+// - the collision resistance of the Merkle commitments, half the hash output size.
+pub fn conjectured_security(params: &FriParams, field_bits: f64) -> f64 {
+    let query_bits = params.number_of_queries() as f64 * (params.blowup() as f64).log2()
+        + params.grinding_bits() as f64;
+    let commit_bits = field_bits - (params.domain_size() as f64).log2();
+
+    query_bits
+        .min(commit_bits)
+        .min(collision_bits(params))
+        .max(0.0)
+}
+
+// Returns the provable bits of security of `params`, following the Johnson bound analysis of FRI
+// (Ben-Sasson, Carmon, Ishai, Kopparty and Saraf, "Proximity Gaps for Reed-Solomon Codes", as used
+// in the ethSTARK documentation). For a proximity parameter `m >= 3`, with `rho = 1 / blowup`,
+// `n` the domain size and `|F|` the field size, the soundness error is at most
+//
+//     (m + 1/2)^7 / (3 rho^(3/2)) * n^2 / |F|
+//   + (2m + 1) (n + 1) / sqrt(rho) * (sum of folding factors) / |F|
+//   + ((1 + 1 / 2m) sqrt(rho))^number_of_queries / 2^grinding_bits
+//
+// and the best `m` is kept. The result is also capped by the collision resistance of the hash.
+pub fn provable_security(params: &FriParams, field_bits: f64) -> f64 {
+    let rho = 1.0 / params.blowup() as f64;
+    let log_n = (params.domain_size() as f64).log2();
+    let log_n_plus_one = (params.domain_size() as f64 + 1.0).log2();
+    let folding_sum = (params.number_layers() * params.folding_factor()) as f64;
+
+    let best = (MIN_PROXIMITY_PARAMETER..=MAX_PROXIMITY_PARAMETER)
+        .map(|m| {
+            let m = m as f64;
+            let commit_error =
+                ((m + 0.5).powi(7) / (3.0 * rho.powf(1.5))).log2() + 2.0 * log_n - field_bits;
+            let folding_error =
+                ((2.0 * m + 1.0) / rho.sqrt() * folding_sum).log2() + log_n_plus_one - field_bits;
+            let query_error = params.number_of_queries() as f64
+                * ((1.0 + 0.5 / m) * rho.sqrt()).log2()
+                - params.grinding_bits() as f64;
+
+            -log2_sum(&[commit_error, folding_error, query_error])
+        })
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    best.min(collision_bits(params)).max(0.0)
+}
+
+// Returns the smallest number of queries for which `params` reaches `target_bits` of security under `bound`,
+// or `None` if the target cannot be reached by adding queries, e.g. when it exceeds what the field
+// or the hash function allow. All other parameters are kept as they are.
+pub fn minimal_queries(
+    params: &FriParams,
+    field_bits: f64,
+    target_bits: f64,
+    bound: SoundnessBound,
+) -> Option<usize> {
+    let security = |number_of_queries: usize| {
+        let candidate = params
+            .with_number_of_queries(number_of_queries)
+            .expect("the search only tries between 1 and the domain size queries");
+        SecurityEstimate::new(&candidate, field_bits).bits(bound)
+    };
+
+    // Security does not decrease with the number of queries, so binary search for the first one reaching the target.
+    let (mut low, mut high) = (1, params.domain_size());
+    if security(high) < target_bits {
+        return None;
+    }
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if security(middle) >= target_bits {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+
+    Some(low)
+}
+
+// Returns the collision resistance of the Merkle commitments in bits.
+fn collision_bits(params: &FriParams) -> f64 {
+    params.hash().output_bits() as f64 / 2.0
+}
+
+// Returns `log2(sum_i 2^x_i)` without leaving the log domain.
+fn log2_sum(logs: &[f64]) -> f64 {
+    let max = logs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    max + logs.iter().map(|x| (x - max).exp2()).sum::<f64>().log2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HashFunction;
+
+    fn params(number_of_queries: usize, grinding_bits: u32) -> FriParams {
+        FriParams::new(
+            1 << 16,
+            8,
+            4,
+            number_of_queries,
+            0,
+            grinding_bits,
+            HashFunction::Sha256,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn conjectured_security_counts_queries_and_grinding() {
+        // 3 bits per query at rate 1/8, plus the proof-of-work
+        let estimate = SecurityEstimate::new(&params(27, 16), 192.0);
+        assert_eq!(estimate.conjectured_bits, 97.0);
+
+        // limited by the field: a 2^19 domain in a 64-bit field
+        assert_eq!(conjectured_security(&params(27, 16), 64.0), 45.0);
+
+        // limited by the collision resistance of SHA-256
+        assert_eq!(conjectured_security(&params(60, 0), 256.0), 128.0);
+    }
+
+    #[test]
+    fn provable_security_is_below_conjectured() {
+        for queries in [10, 27, 50, 80] {
+            let estimate = SecurityEstimate::new(&params(queries, 0), 192.0);
+            assert!(estimate.provable_bits > 0.0);
+            assert!(estimate.provable_bits < estimate.conjectured_bits);
+        }
+
+        // a 97 element field gives no provable security at all
+        assert_eq!(provable_security(&params(80, 0), (97f64).log2()), 0.0);
+    }
+
+    #[test]
+    fn minimal_queries_reach_the_target() {
+        let base = params(1, 0);
+        for bound in [SoundnessBound::Conjectured, SoundnessBound::Provable] {
+            let queries = minimal_queries(&base, 192.0, 80.0, bound).unwrap();
+            let reached = SecurityEstimate::new(&params(queries, 0), 192.0).bits(bound);
+            let below = SecurityEstimate::new(&params(queries - 1, 0), 192.0).bits(bound);
+
+            assert!(reached >= 80.0);
+            assert!(below < 80.0);
+        }
+
+        assert_eq!(
+            minimal_queries(&base, 192.0, 80.0, SoundnessBound::Conjectured),
+            Some(27)
+        );
+        // the hash caps the security at 128 bits, and the field at 64 - 19
+        assert_eq!(
+            minimal_queries(&base, 192.0, 130.0, SoundnessBound::Conjectured),
+            None
+        );
+        assert_eq!(
+            minimal_queries(&base, 64.0, 50.0, SoundnessBound::Provable),
+            None
+        );
+    }
+}